@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
 
 const DEFAULT_CONFIG_FILE_PATHNAME: &str = "evaluation/config.toml";
 
@@ -6,6 +6,106 @@ const DEFAULT_CONFIG_FILE_PATHNAME: &str = "evaluation/config.toml";
 #[clap(author, version, about)]
 pub struct Arguments {
     /// Path of the configuration file.
-    #[clap(short = 'c', long = "config", default_value_t = String::from(DEFAULT_CONFIG_FILE_PATHNAME))]
+    #[clap(short = 'c', long = "config", default_value_t = String::from(DEFAULT_CONFIG_FILE_PATHNAME), global = true)]
     pub config: String,
+
+    /// Subcommand to execute.
+    /// If not specified, `run` is executed with the default options.
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Builds the programs and evaluates the submission code for each seed.
+    Run(RunArguments),
+
+    /// Builds the local tester and the submission code without evaluating.
+    Build,
+
+    /// Shows the statistics of a stored evaluation again.
+    ///
+    /// The evaluation is specified by the path of a CSV file of evaluation records,
+    /// the path of a run file, or the identifier of a run saved in the history directory.
+    Report {
+        /// Evaluation to show.
+        /// If not specified, the CSV file of `evaluation_record` is used.
+        run: Option<String>,
+    },
+
+    /// Compares two runs seed by seed.
+    ///
+    /// Each run is specified by the path of a CSV file of evaluation records,
+    /// the path of a run file, or the identifier of a run saved in the history directory.
+    Compare {
+        /// Run used as the baseline.
+        run_a: String,
+
+        /// Run compared against the baseline.
+        run_b: String,
+    },
+
+    /// Evaluates the submission code for every combination of the parameters declared in the configuration.
+    ///
+    /// The parameters are passed to the execution commands as environment variables `AHC_PARAM_NAME` and `{param.NAME}` placeholders.
+    /// Failed seeds are recorded instead of stopping the sweep.
+    Sweep(SearchArguments),
+
+    /// Searches for the best values of the parameters declared in the configuration.
+    ///
+    /// The evaluations are saved in `tune.trial_file` and reused when the search is executed again.
+    Tune(SearchArguments),
+
+    /// Writes the default configuration file.
+    Init {
+        /// Overwrites the configuration file if it already exists.
+        #[clap(short = 'f', long = "force")]
+        force: bool,
+    },
+
+    /// Removes the output files and the CSV file of evaluation records.
+    ///
+    /// The files and the artifact directories saved for each seed in the seed file are removed as well.
+    Clean,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct RunArguments {
+    /// Label attached to the run saved in the history.
+    #[clap(short = 'l', long = "label")]
+    pub label: Option<String>,
+
+    /// Skips building the local tester and the submission code.
+    #[clap(long = "no-build")]
+    pub no_build: bool,
+
+    /// Seeds to evaluate.
+    #[clap(flatten)]
+    pub seeds: SeedArguments,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct SearchArguments {
+    /// Skips building the local tester and the submission code.
+    #[clap(long = "no-build")]
+    pub no_build: bool,
+
+    /// Seeds to evaluate for each value of the parameters.
+    #[clap(flatten)]
+    pub seeds: SeedArguments,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct SeedArguments {
+    /// Seeds to evaluate instead of the seed file, such as `0..100,205,300-310`.
+    #[clap(long = "seeds")]
+    pub seeds: Option<String>,
+
+    /// Number of seeds selected at random from the seed list.
+    #[clap(long = "sample")]
+    pub sample: Option<usize>,
+
+    /// Seed of the random selection of `--sample`.
+    #[clap(long = "sample-seed", default_value_t = 0)]
+    pub sample_seed: u64,
 }
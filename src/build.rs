@@ -1,4 +1,4 @@
-use std::process::Command;
+use std::process::{ExitStatus, Output, Stdio};
 
 use anyhow::{ensure, Context};
 
@@ -6,15 +6,19 @@ use crate::config::Config;
 
 /// Builds the submission code.
 pub fn build_submission(config: &Config) -> anyhow::Result<()> {
-    let cmd_args = &config.command.build.submission;
+    let command_spec = config.command_for_build_submission();
+    let cmd_args = &command_spec.cmd_args;
 
     // Skips build if build command for submission code is empty.
-    let Some(program) = cmd_args.first() else {
+    if cmd_args.is_empty() {
         return Ok(());
-    };
+    }
 
-    let process_handle = Command::new(program)
-        .args(&cmd_args[1..])
+    // Captures the outputs so that they can be shown if the build fails.
+    let process_handle = command_spec
+        .to_command()?
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
         .spawn()
         .with_context(|| {
             format!(
@@ -32,10 +36,7 @@ List of arguments: {:?}
 
     ensure!(
         output.status.success(),
-        BuildCommandError {
-            cmd_args: config.command.build.submission.clone(),
-            output
-        }
+        BuildCommandError::new(config, cmd_args.clone(), &output)
     );
 
     Ok(())
@@ -43,15 +44,19 @@ List of arguments: {:?}
 
 /// Builds the local tester.
 pub fn build_tester(config: &Config) -> anyhow::Result<()> {
-    let cmd_args = &config.command.build.tester;
+    let command_spec = config.command_for_build_tester();
+    let cmd_args = &command_spec.cmd_args;
 
     // Skips build if build command for local tester is empty.
-    let Some(program) = cmd_args.first() else {
+    if cmd_args.is_empty() {
         return Ok(());
-    };
+    }
 
-    let process_handle = Command::new(program)
-        .args(&cmd_args[1..])
+    // Captures the outputs so that they can be shown if the build fails.
+    let process_handle = command_spec
+        .to_command()?
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
         .spawn()
         .with_context(|| {
             format!(
@@ -69,10 +74,7 @@ List of arguments: {:?}
 
     ensure!(
         output.status.success(),
-        BuildCommandError {
-            cmd_args: config.command.build.tester.clone(),
-            output
-        }
+        BuildCommandError::new(config, cmd_args.clone(), &output)
     );
 
     Ok(())
@@ -81,14 +83,29 @@ List of arguments: {:?}
 #[derive(Debug)]
 pub struct BuildCommandError {
     pub cmd_args: Vec<String>,
-    pub output: std::process::Output,
+    pub status: ExitStatus,
+
+    /// Excerpt of the standard output.
+    pub stdout: String,
+
+    /// Excerpt of the standard error output.
+    pub stderr: String,
+}
+
+impl BuildCommandError {
+    /// Creates the error of the failed build command.
+    fn new(config: &Config, cmd_args: Vec<String>, output: &Output) -> Self {
+        Self {
+            cmd_args,
+            status: output.status,
+            stdout: config.output_excerpt(&output.stdout),
+            stderr: config.output_excerpt(&output.stderr),
+        }
+    }
 }
 
 impl std::fmt::Display for BuildCommandError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let stdout = String::from_utf8(self.output.stdout.clone()).unwrap();
-        let stderr = String::from_utf8(self.output.stderr.clone()).unwrap();
-
         write!(
             f,
             "
@@ -106,7 +123,7 @@ Command line arguments: {:?},
 {}
 --------------------------------------------------------------------------------
 ",
-            self.output.status, self.cmd_args, stdout, stderr,
+            self.status, self.cmd_args, self.stdout, self.stderr,
         )
     }
 }
@@ -0,0 +1,407 @@
+//! Provides a comparison of two runs joined by seed.
+
+use std::collections::HashMap;
+
+use anyhow::ensure;
+use itertools::Itertools;
+
+use crate::{
+    config::Objective,
+    evaluation::{is_better, relative_score, EvaluationRecord},
+    statistics::{self, TestResult},
+};
+
+/// Number of seeds shown in each list of the largest regressions and improvements.
+const EXTREME_SEED_NUM: usize = 5;
+
+/// Confidence level of the bootstrap confidence intervals.
+const CONFIDENCE_LEVEL: f64 = 0.95;
+
+/// Number of resamplings for the bootstrap confidence intervals.
+const BOOTSTRAP_ITERATION_NUM: usize = 10000;
+
+/// Outcome of run B against run A for a seed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Outcome {
+    Win,
+    Lose,
+    Draw,
+}
+
+/// Records of the two runs for the same seed.
+#[derive(Debug, Clone, Copy)]
+struct RecordPair<'a> {
+    record_a: &'a EvaluationRecord,
+    record_b: &'a EvaluationRecord,
+}
+
+impl RecordPair<'_> {
+    /// Returns the seed of the records.
+    fn seed(&self) -> usize {
+        self.record_a.seed
+    }
+
+    /// Returns the outcome of run B against run A.
+    ///
+    /// A seed that failed in only one of the runs is a loss for that run.
+    fn outcome(&self, objective: Objective) -> Outcome {
+        match (self.record_a.score, self.record_b.score) {
+            (Some(score_a), Some(score_b)) if is_better(score_b, score_a, objective) => {
+                Outcome::Win
+            }
+            (Some(score_a), Some(score_b)) if is_better(score_a, score_b, objective) => {
+                Outcome::Lose
+            }
+            (Some(_), None) => Outcome::Lose,
+            (None, Some(_)) => Outcome::Win,
+            _ => Outcome::Draw,
+        }
+    }
+
+    /// Returns the relative scores of run A and run B against the better score of the two.
+    fn relative_scores(&self, objective: Objective) -> Option<(f64, f64)> {
+        let score_a = self.record_a.score?;
+        let score_b = self.record_b.score?;
+
+        let best_score = if is_better(score_b, score_a, objective) {
+            score_b
+        } else {
+            score_a
+        };
+
+        Some((
+            relative_score(score_a, best_score, objective),
+            relative_score(score_b, best_score, objective),
+        ))
+    }
+
+    /// Returns the difference of the relative scores (run B - run A).
+    fn relative_score_diff(&self, objective: Objective) -> Option<f64> {
+        self.relative_scores(objective)
+            .map(|(relative_score_a, relative_score_b)| relative_score_b - relative_score_a)
+    }
+}
+
+/// Shows the comparison of run B against run A.
+///
+/// Seeds are joined between the runs, and seeds that exist in only one of the runs are ignored.
+pub fn compare_runs(
+    objective: Objective,
+    (name_a, records_a): (&str, &[EvaluationRecord]),
+    (name_b, records_b): (&str, &[EvaluationRecord]),
+) -> anyhow::Result<()> {
+    let seed_to_record_b: HashMap<usize, &EvaluationRecord> = records_b
+        .iter()
+        .map(|record| (record.seed, record))
+        .collect();
+
+    let record_pairs = records_a
+        .iter()
+        .filter_map(|record_a| {
+            seed_to_record_b
+                .get(&record_a.seed)
+                .map(|&record_b| RecordPair { record_a, record_b })
+        })
+        .sorted_by_key(|pair| pair.seed())
+        .collect_vec();
+
+    ensure!(
+        !record_pairs.is_empty(),
+        "There are no seeds common to both runs."
+    );
+
+    show_per_seed_comparison(objective, &record_pairs);
+    println!();
+    show_summary(objective, (name_a, name_b), &record_pairs);
+    println!();
+    show_significance(objective, &record_pairs);
+    println!();
+    show_extreme_seeds(objective, &record_pairs);
+
+    Ok(())
+}
+
+/// Shows the scores of both runs and their differences for each seed.
+fn show_per_seed_comparison(objective: Objective, record_pairs: &[RecordPair]) {
+    println!("[Per-Seed Comparison]");
+    println!(
+        "{:>8}  {:>14}  {:>14}  {:>14}  {:>10}",
+        "Seed", "Score A", "Score B", "Diff", "Rel. Diff"
+    );
+
+    let format_score = |record: &EvaluationRecord| match record.score {
+        Some(score) => score.to_string(),
+        None => record.status.to_string(),
+    };
+
+    for pair in record_pairs {
+        let diff = match (pair.record_a.score, pair.record_b.score) {
+            (Some(score_a), Some(score_b)) => format!("{:+}", score_b - score_a),
+            _ => "-".to_owned(),
+        };
+        let relative_score_diff = match pair.relative_score_diff(objective) {
+            Some(relative_score_diff) => format!("{:+.3}%", 100.0 * relative_score_diff),
+            None => "-".to_owned(),
+        };
+
+        println!(
+            "{:>8}  {:>14}  {:>14}  {:>14}  {:>10}",
+            pair.seed(),
+            format_score(pair.record_a),
+            format_score(pair.record_b),
+            diff,
+            relative_score_diff,
+        );
+    }
+}
+
+/// Shows the win/lose/draw counts and the differences of the averages.
+fn show_summary(objective: Objective, (name_a, name_b): (&str, &str), record_pairs: &[RecordPair]) {
+    let outcome_counts = record_pairs
+        .iter()
+        .map(|pair| pair.outcome(objective))
+        .counts();
+    let count = |outcome: Outcome| outcome_counts.get(&outcome).copied().unwrap_or_default();
+
+    // Averages are taken over the seeds that were accepted in both runs.
+    let scored_pairs = record_pairs
+        .iter()
+        .filter(|pair| pair.record_a.score.is_some() && pair.record_b.score.is_some())
+        .collect_vec();
+    let average = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+
+    print!(
+        "\
+[Comparison]
+Run A: {}
+Run B: {}
+Common Seeds: {}
+Win / Lose / Draw (B against A): {} / {} / {}
+",
+        name_a,
+        name_b,
+        record_pairs.len(),
+        count(Outcome::Win),
+        count(Outcome::Lose),
+        count(Outcome::Draw),
+    );
+
+    if scored_pairs.is_empty() {
+        println!("There are no seeds accepted in both runs.");
+        return;
+    }
+
+    let scores_a = scored_pairs
+        .iter()
+        .map(|pair| pair.record_a.score.unwrap())
+        .collect_vec();
+    let scores_b = scored_pairs
+        .iter()
+        .map(|pair| pair.record_b.score.unwrap())
+        .collect_vec();
+
+    let (relative_scores_a, relative_scores_b): (Vec<f64>, Vec<f64>) = scored_pairs
+        .iter()
+        .map(|pair| pair.relative_scores(objective).unwrap())
+        .unzip();
+    let (avg_relative_score_a, avg_relative_score_b) =
+        (average(&relative_scores_a), average(&relative_scores_b));
+
+    let exe_times_a = scored_pairs
+        .iter()
+        .filter_map(|pair| pair.record_a.execution_time)
+        .collect_vec();
+    let exe_times_b = scored_pairs
+        .iter()
+        .filter_map(|pair| pair.record_b.execution_time)
+        .collect_vec();
+
+    print!(
+        "\
+Average Score: {}
+Average Relative Score: {:.3}% -> {:.3}% ({:+.3} pt)
+Average Execution Time: {}
+",
+        format_average_change(&scores_a, &scores_b),
+        100.0 * avg_relative_score_a,
+        100.0 * avg_relative_score_b,
+        100.0 * (avg_relative_score_b - avg_relative_score_a),
+        format_average_change(&exe_times_a, &exe_times_b),
+    );
+}
+
+/// Formats the change of the average from `values_a` to `values_b` with its difference and percentage.
+///
+/// An average of no values and a percentage relative to zero are shown as `-`.
+fn format_average_change(values_a: &[f64], values_b: &[f64]) -> String {
+    let average = |values: &[f64]| {
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    };
+
+    let (Some(avg_a), Some(avg_b)) = (average(values_a), average(values_b)) else {
+        let format = |avg: Option<f64>| avg.map_or("-".to_owned(), |avg| format!("{:.3}", avg));
+        return format!(
+            "{} -> {}",
+            format(average(values_a)),
+            format(average(values_b))
+        );
+    };
+
+    let percentage = if avg_a != 0.0 {
+        format!("{:+.3}%", 100.0 * (avg_b - avg_a) / avg_a.abs())
+    } else {
+        "-".to_owned()
+    };
+
+    format!(
+        "{:.3} -> {:.3} ({:+.3}, {})",
+        avg_a,
+        avg_b,
+        avg_b - avg_a,
+        percentage
+    )
+}
+
+/// Shows the paired statistical tests of the differences between the runs.
+///
+/// The tests are performed on the differences of the relative scores of the seeds accepted in both runs,
+/// so that seeds with large scores do not dominate.
+fn show_significance(objective: Objective, record_pairs: &[RecordPair]) {
+    let score_diffs = record_pairs
+        .iter()
+        .filter_map(|pair| Some(pair.record_b.score? - pair.record_a.score?))
+        .collect_vec();
+    let relative_score_diffs = record_pairs
+        .iter()
+        .filter_map(|pair| pair.relative_score_diff(objective))
+        .collect_vec();
+
+    println!(
+        "[Significance] (relative score difference B - A, n = {})",
+        relative_score_diffs.len()
+    );
+
+    if relative_score_diffs.is_empty() {
+        println!("There are no seeds accepted in both runs.");
+        return;
+    }
+
+    let format_result = |result: Option<TestResult>, statistic_name: &str| match result {
+        Some(result) => format!(
+            "{} = {:.3}, p = {:.4}",
+            statistic_name, result.statistic, result.p_value
+        ),
+        None => "Not applicable".to_owned(),
+    };
+
+    let (relative_lower, relative_upper) = statistics::bootstrap_mean_confidence_interval(
+        &relative_score_diffs,
+        CONFIDENCE_LEVEL,
+        BOOTSTRAP_ITERATION_NUM,
+    );
+    let (score_lower, score_upper) = statistics::bootstrap_mean_confidence_interval(
+        &score_diffs,
+        CONFIDENCE_LEVEL,
+        BOOTSTRAP_ITERATION_NUM,
+    );
+
+    print!(
+        "\
+Paired t-test: {}
+Wilcoxon signed-rank test: {}
+Sign test: {}
+Bootstrap {:.0}% CI of Mean Relative Score Difference: [{:+.3} pt, {:+.3} pt]
+Bootstrap {:.0}% CI of Mean Score Difference: [{:+.3}, {:+.3}]
+",
+        format_result(statistics::paired_t_test(&relative_score_diffs), "t"),
+        format_result(
+            statistics::wilcoxon_signed_rank_test(&relative_score_diffs),
+            "z"
+        ),
+        match statistics::sign_test(&relative_score_diffs) {
+            Some(result) => format!(
+                "{} positive / {} negative, p = {:.4}",
+                result.statistic,
+                relative_score_diffs
+                    .iter()
+                    .filter(|&&diff| diff < 0.0)
+                    .count(),
+                result.p_value
+            ),
+            None => "Not applicable".to_owned(),
+        },
+        100.0 * CONFIDENCE_LEVEL,
+        100.0 * relative_lower,
+        100.0 * relative_upper,
+        100.0 * CONFIDENCE_LEVEL,
+        score_lower,
+        score_upper,
+    );
+}
+
+/// Shows the seeds with the largest regressions and improvements in relative score.
+fn show_extreme_seeds(objective: Objective, record_pairs: &[RecordPair]) {
+    let diffs = record_pairs
+        .iter()
+        .filter_map(|pair| Some((pair, pair.relative_score_diff(objective)?)))
+        .sorted_by(|(_, x), (_, y)| x.total_cmp(y))
+        .collect_vec();
+
+    let show_list = |title: &str, list: &[&(&RecordPair, f64)]| {
+        println!("[{}]", title);
+
+        if list.is_empty() {
+            println!("None");
+            return;
+        }
+
+        for (pair, diff) in list {
+            println!(
+                "seed = {}: {} -> {} ({:+.3}%)",
+                pair.seed(),
+                pair.record_a.score.unwrap(),
+                pair.record_b.score.unwrap(),
+                100.0 * diff,
+            );
+        }
+    };
+
+    let regressions = diffs
+        .iter()
+        .filter(|(_, diff)| *diff < 0.0)
+        .take(EXTREME_SEED_NUM)
+        .collect_vec();
+    let improvements = diffs
+        .iter()
+        .rev()
+        .filter(|(_, diff)| *diff > 0.0)
+        .take(EXTREME_SEED_NUM)
+        .collect_vec();
+
+    show_list("Largest Regressions", &regressions);
+    println!();
+    show_list("Largest Improvements", &improvements);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_changes_of_averages() {
+        assert_eq!(
+            format_average_change(&[1.0, 3.0], &[3.0, 5.0]),
+            "2.000 -> 4.000 (+2.000, +100.000%)"
+        );
+        assert_eq!(
+            format_average_change(&[-2.0], &[-1.0]),
+            "-2.000 -> -1.000 (+1.000, +50.000%)"
+        );
+        assert_eq!(
+            format_average_change(&[0.0], &[1.0]),
+            "0.000 -> 1.000 (+1.000, -)"
+        );
+        assert_eq!(format_average_change(&[], &[1.0]), "- -> 1.000");
+        assert_eq!(format_average_change(&[], &[]), "- -> -");
+    }
+}
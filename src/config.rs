@@ -1,12 +1,17 @@
 use std::{
+    collections::BTreeMap,
     fs::read_to_string,
     path::{Path, PathBuf},
+    process::Command,
+    time::Duration,
 };
 
-use anyhow::Context;
+use anyhow::{bail, ensure, Context};
 use itertools::Itertools;
 use serde::Deserialize;
 
+use crate::{excerpt, template};
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct ThreadConfig {
     /// Number of threads used for evaluation.
@@ -25,34 +30,177 @@ pub struct PathConfig {
     /// Path of the directory of output files.
     pub output_dir: std::path::PathBuf,
 
+    /// Name pattern of the input file in `input_dir`.
+    /// The placeholder `{seed}` can be used with a width such as `{seed:04}`.
+    #[serde(default = "default_file_pattern")]
+    pub input_file: String,
+
+    /// Name pattern of the output file in `output_dir`.
+    /// The placeholder `{seed}` can be used with a width such as `{seed:04}`.
+    #[serde(default = "default_file_pattern")]
+    pub output_file: String,
+
+    /// Path pattern of the file in which the standard error output of the submission code is saved for each seed.
+    /// In integrated mode, the standard error output of the local tester is saved instead unless `log_file` is specified.
+    /// If not specified, it is not saved.
+    pub stderr_file: Option<String>,
+
+    /// Path pattern of the file in which the standard error output of the local tester is saved for each seed.
+    /// In integrated mode, it includes the standard error output of the submission code, which is not saved to `stderr_file`.
+    /// If not specified, it is not saved.
+    pub log_file: Option<String>,
+
+    /// Path pattern of the file in which the full output of the failed command is saved for each seed.
+    /// If not specified, it is not saved.
+    pub failure_log_file: Option<String>,
+
     /// Path of the file that outputs the score and execution time for each seed.
     pub evaluation_record: std::path::PathBuf,
+
+    /// Path of the file that stores the best known score for each seed.
+    /// If specified, the file is updated after every evaluation and relative scores are calculated.
+    pub best_score: Option<std::path::PathBuf>,
+
+    /// Path of the directory in which every evaluation is saved as a run.
+    /// If not specified, the history is not saved.
+    pub history_dir: Option<std::path::PathBuf>,
+}
+
+/// Default value of `input_file` and `output_file`.
+fn default_file_pattern() -> String {
+    "{seed:04}.txt".to_owned()
+}
+
+impl PathConfig {
+    /// Validates the patterns of the paths.
+    fn validate(&self) -> anyhow::Result<()> {
+        // Checks that the patterns contain only the available placeholders.
+        let seed_patterns = [
+            ("path.input_file", Some(&self.input_file)),
+            ("path.output_file", Some(&self.output_file)),
+            ("path.stderr_file", self.stderr_file.as_ref()),
+            ("path.log_file", self.log_file.as_ref()),
+            ("path.failure_log_file", self.failure_log_file.as_ref()),
+        ];
+        for (key, pattern) in seed_patterns {
+            if let Some(pattern) = pattern {
+                template::validate(pattern, &["seed"])
+                    .with_context(|| format!("Invalid pattern in `{}`.", key))?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Build {
     /// Build command for submission code.
+    ///
+    /// The placeholders `{input-dir}`, `{output-dir}` and `{run-id}` can be used (see `Execute::tester`).
     pub submission: Vec<String>,
 
     /// Build command for local tester.
+    ///
+    /// The same placeholders as `submission` can be used.
     pub tester: Vec<String>,
+
+    /// Environment variables set for the build command for submission code.
+    /// The same placeholders as `submission` can be used in the values.
+    #[serde(default)]
+    pub submission_env: BTreeMap<String, String>,
+
+    /// Environment variables set for the build command for local tester.
+    /// The same placeholders as `tester` can be used in the values.
+    #[serde(default)]
+    pub tester_env: BTreeMap<String, String>,
+
+    /// Working directory of the build command for submission code.
+    /// If not specified, the current directory is used.
+    pub submission_cwd: Option<String>,
+
+    /// Working directory of the build command for local tester.
+    /// If not specified, the current directory is used.
+    pub tester_cwd: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Execute {
     /// Command line arguments to execute the submission code.
+    ///
+    /// The same placeholders as `tester` can be used except `{cmd}`.
     pub submission: Vec<String>,
 
     /// Command line arguments to execute the local tester.
     ///
-    /// The following placeholders can be used (Placeholders must be quoted independently).
-    /// - `{input-path}`: The path of the input file corresponding to the seed.
-    /// - `{output-path}`: The path of the output file corresponding to the seed.
-    /// - `{submission-execute}`: Execution command of the submission code.
+    /// The following placeholders can be used anywhere in an argument (e.g. `--in={input}`).
+    /// - `{seed}`: The seed.
+    /// - `{input}` (or `{input-path}`): The path of the input file corresponding to the seed.
+    /// - `{output}` (or `{output-path}`): The path of the output file corresponding to the seed.
+    /// - `{cmd}` (or `{submission-execute}`): Execution command of the submission code,
+    ///   with each argument quoted for the shell if necessary.
+    /// - `{input-dir}`: The directory of the input files.
+    /// - `{output-dir}`: The directory of the output files.
+    /// - `{run-id}`: The identifier of the run, which is empty outside of a run.
+    ///
+    /// If `tester_cwd` is specified, the paths are made absolute.
     pub tester: Vec<String>,
 
+    /// Environment variables set for the submission code in addition to `AHC_SEED`, `AHC_INPUT`, `AHC_OUTPUT` and `AHC_RUN_ID`.
+    /// The same placeholders as `submission` can be used in the values.
+    /// In integrated mode, they must not be specified since the submission code is executed by the local tester.
+    #[serde(default)]
+    pub submission_env: BTreeMap<String, String>,
+
+    /// Environment variables set for the local tester in addition to `AHC_SEED`, `AHC_INPUT`, `AHC_OUTPUT` and `AHC_RUN_ID`.
+    /// The same placeholders as `tester` can be used in the values.
+    #[serde(default)]
+    pub tester_env: BTreeMap<String, String>,
+
+    /// Working directory of the submission code.
+    /// If not specified, the current directory is used.
+    /// In integrated mode, it must not be specified since the submission code is executed by the local tester.
+    pub submission_cwd: Option<String>,
+
+    /// Working directory of the local tester (e.g. `"tools"`).
+    /// If not specified, the current directory is used.
+    pub tester_cwd: Option<String>,
+
     /// Set this flag to `true` if the submission code is to be executed via the local tester rather than independently.
     pub integrated: bool,
+
+    /// Time limit in seconds for each seed.
+    /// If exceeded, the process group is terminated and the seed is recorded as TLE.
+    /// If not specified, the processes are waited for without limit.
+    pub timeout: Option<f64>,
+
+    /// Time in seconds to wait after `SIGTERM` before killing the process group with `SIGKILL`.
+    #[serde(default = "default_kill_grace_period")]
+    pub kill_grace_period: f64,
+
+    /// Memory limit in MiB for the submission code.
+    /// The address space of the process is limited, so memory allocation beyond it fails,
+    /// and the seed is recorded as MLE if the process fails after its peak resident set size reaches 90% of the limit.
+    /// In integrated mode, the limit is applied only to the submission code by wrapping `{cmd}` with `ulimit -v`.
+    /// Runtimes reserving a large address space (e.g. Go, JVM, AddressSanitizer) may fail under the limit.
+    /// If not specified, the memory usage is not limited.
+    pub memory_limit: Option<u64>,
+
+    /// Threshold in MiB of the peak resident set size of the submission code.
+    /// If the peak resident set size exceeds it, the seed is recorded as MLE.
+    /// In integrated mode, it is compared with the peak of the local tester and the submission code.
+    /// If not specified, the peak resident set size is not checked.
+    pub rss_limit: Option<u64>,
+
+    /// Set this flag to `true` to continue the evaluation of the other seeds when a seed fails.
+    /// Failed seeds are recorded with their verdicts and listed at the end.
+    #[serde(default)]
+    pub keep_going: bool,
+}
+
+/// Default value of `kill_grace_period`.
+fn default_kill_grace_period() -> f64 {
+    1.0
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -62,6 +210,776 @@ pub struct CommandConfig {
 
     /// Command line arguments to execute codes.
     pub execute: Execute,
+
+    /// Command line arguments to generate the missing input files.
+    /// If not specified, the input files must be prepared in advance.
+    ///
+    /// If `{seeds-file}` is used, the generator is executed once for all missing seeds.
+    /// Otherwise, it is executed for each missing seed, and its standard output is saved as the input file
+    /// unless the generator writes the input file by itself.
+    /// The following placeholders can be used.
+    /// - `{seed}`: The seed.
+    /// - `{input}`: The path of the input file corresponding to the seed.
+    /// - `{param.NAME}`: The value of the parameter `NAME=value` written on the line of the seed in the seed file.
+    /// - `{seeds-file}`: The path of a temporary file listing the missing seeds, one per line,
+    ///   each followed by the values of its parameters in the order of their names.
+    ///   All the missing seeds must have parameters of the same names.
+    /// - `{gen-dir}`: The path of a temporary directory. Files named `0000.txt`, `0001.txt`, ... in it
+    ///   are taken as the input files of the seeds on the corresponding lines of `{seeds-file}`.
+    ///
+    /// `{input-dir}`, `{output-dir}` and `{run-id}` can also be used (see `Execute::tester`).
+    pub generate: Option<Vec<String>>,
+
+    /// Environment variables set for the generator.
+    /// The same placeholders as `generate` can be used in the values.
+    #[serde(default)]
+    pub generate_env: BTreeMap<String, String>,
+
+    /// Working directory of the generator.
+    /// If not specified, the current directory is used.
+    pub generate_cwd: Option<String>,
+
+    /// Number of bytes shown from the beginning of the output of a failed command.
+    #[serde(default = "default_error_output_head")]
+    pub error_output_head: usize,
+
+    /// Number of bytes shown from the end of the output of a failed command.
+    /// The bytes between the head and the tail are omitted.
+    #[serde(default = "default_error_output_tail")]
+    pub error_output_tail: usize,
+}
+
+/// Default value of `error_output_head`.
+fn default_error_output_head() -> usize {
+    2048
+}
+
+/// Default value of `error_output_tail`.
+fn default_error_output_tail() -> usize {
+    2048
+}
+
+impl CommandConfig {
+    /// Validates the limits and the placeholders of the commands.
+    ///
+    /// The placeholders of the parameters are available in the execution commands and the generator.
+    fn validate(&self, params: &[ParamConfig]) -> anyhow::Result<()> {
+        let build = &self.build;
+        let execute = &self.execute;
+
+        ensure!(
+            !execute.submission.is_empty() && !execute.tester.is_empty(),
+            "`command.execute.submission` and `command.execute.tester` must not be empty."
+        );
+        ensure!(
+            execute
+                .timeout
+                .is_none_or(|timeout| timeout.is_finite() && timeout > 0.0),
+            "`timeout` must be a positive number of seconds."
+        );
+        ensure!(
+            execute.kill_grace_period.is_finite() && execute.kill_grace_period >= 0.0,
+            "`kill_grace_period` must be a non-negative number of seconds."
+        );
+
+        if execute.integrated {
+            ensure!(
+                execute.submission_env.is_empty() && execute.submission_cwd.is_none(),
+                "`command.execute.submission_env` and `command.execute.submission_cwd` cannot be used in integrated mode."
+            );
+        }
+
+        let command_templates = [
+            (
+                "command.build.submission",
+                &build.submission,
+                &build.submission_env,
+                &build.submission_cwd,
+                BUILD_PLACEHOLDERS,
+            ),
+            (
+                "command.build.tester",
+                &build.tester,
+                &build.tester_env,
+                &build.tester_cwd,
+                BUILD_PLACEHOLDERS,
+            ),
+            (
+                "command.execute.submission",
+                &execute.submission,
+                &execute.submission_env,
+                &execute.submission_cwd,
+                SEED_PLACEHOLDERS,
+            ),
+            (
+                "command.execute.tester",
+                &execute.tester,
+                &execute.tester_env,
+                &execute.tester_cwd,
+                TESTER_PLACEHOLDERS,
+            ),
+        ];
+        for (key, cmd_args, env, cwd, names) in command_templates {
+            // Parameters are available in the execution commands.
+            let param_available = key.starts_with("command.execute.");
+            for template in cmd_args.iter().chain(env.values()).chain(cwd) {
+                template::expand(template, |name| {
+                    let is_param = name.strip_prefix("param.").is_some_and(|param_name| {
+                        params.iter().any(|param| param.name == param_name)
+                    });
+
+                    (names.contains(&name) || (param_available && is_param)).then(String::new)
+                })
+                .with_context(|| format!("Invalid placeholder in `{}`.", key))?;
+            }
+            for name in env.keys() {
+                ensure!(
+                    !name.is_empty() && !name.contains('='),
+                    "Environment variable name `{}` for `{}` must be non-empty and must not contain `=`.",
+                    name,
+                    key
+                );
+            }
+        }
+
+        if let Some(generate) = &self.generate {
+            ensure!(
+                !generate.is_empty(),
+                "`command.generate` must not be empty."
+            );
+
+            let batch = generate.iter().any(|arg| arg.contains("{seeds-file}"));
+            let names: &[&str] = if batch {
+                &["seeds-file", "gen-dir"]
+            } else {
+                &["seed", "input"]
+            };
+            for template in generate
+                .iter()
+                .chain(self.generate_env.values())
+                .chain(&self.generate_cwd)
+            {
+                template::expand(template, |name| {
+                    (names.contains(&name)
+                        || BUILD_PLACEHOLDERS.contains(&name)
+                        || (!batch && name.starts_with("param.")))
+                    .then(String::new)
+                })
+                .with_context(|| "Invalid placeholder in `command.generate`.")?;
+            }
+            for name in self.generate_env.keys() {
+                ensure!(
+                    !name.is_empty() && !name.contains('='),
+                    "Environment variable name `{}` for `command.generate` must be non-empty and must not contain `=`.",
+                    name
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Location from which the score is retrieved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScoreSource {
+    /// The standard output of the local tester.
+    Stdout,
+
+    /// The standard error output of the local tester.
+    Stderr,
+
+    /// The file specified by `score.file`.
+    File,
+
+    /// The exit code of the local tester.
+    ExitCode,
+}
+
+/// Direction in which the score is optimized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Objective {
+    /// The higher the score, the better.
+    #[default]
+    Maximize,
+
+    /// The lower the score, the better.
+    Minimize,
+}
+
+/// Numeric type of the score.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScoreType {
+    /// The score is an integer.
+    #[default]
+    Integer,
+
+    /// The score is a floating-point number.
+    Float,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScoreConfig {
+    /// Location from which the score is retrieved.
+    /// If not specified, the standard error output is used in integrated mode,
+    /// and the standard output and then the standard error output are used otherwise.
+    pub source: Option<ScoreSource>,
+
+    /// Regular expression for retrieving the score.
+    /// The score must be captured by the named group `score`.
+    #[serde(default = "default_score_pattern")]
+    pub pattern: String,
+
+    /// Path of the file from which the score is retrieved when `source` is `"file"`.
+    ///
+    /// The following placeholders can be used.
+    /// - `{seed}`: The seed.
+    /// - `{input}`: The path of the input file corresponding to the seed.
+    /// - `{output}`: The path of the output file corresponding to the seed.
+    ///
+    /// The file is removed before the seed is evaluated so that a stale score is not retrieved.
+    pub file: Option<String>,
+
+    /// Numeric type of the score.
+    /// A score that is not a finite number is regarded as not found.
+    #[serde(default, rename = "type")]
+    pub score_type: ScoreType,
+
+    /// Direction in which the score is optimized.
+    #[serde(default)]
+    pub objective: Objective,
+}
+
+impl Default for ScoreConfig {
+    fn default() -> Self {
+        Self {
+            source: None,
+            pattern: default_score_pattern(),
+            file: None,
+            score_type: ScoreType::default(),
+            objective: Objective::default(),
+        }
+    }
+}
+
+/// Default value of `pattern`.
+fn default_score_pattern() -> String {
+    r"\bScore *= *(?<score>-?[0-9]+)\b".to_owned()
+}
+
+impl ScoreConfig {
+    /// Validates the source of the score.
+    fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.source != Some(ScoreSource::File) || self.file.is_some(),
+            "`score.file` must be specified when `score.source` is `\"file\"`."
+        );
+
+        if let Some(file) = &self.file {
+            template::validate(file, &["seed", "input", "output"])
+                .with_context(|| "Invalid pattern in `score.file`.")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StatisticsConfig {
+    /// Percentiles (0 to 100) shown in the statistics.
+    #[serde(default = "default_percentiles")]
+    pub percentiles: Vec<f64>,
+
+    /// Number of bins of the histogram shown in the statistics.
+    #[serde(default = "default_histogram_bin_num")]
+    pub histogram_bin_num: usize,
+
+    /// Time limit in seconds of the problem.
+    /// If specified, the number of seeds whose execution time exceeds it is shown.
+    pub time_limit: Option<f64>,
+
+    /// Maximum number of bins into which seeds are grouped by the value of each feature.
+    #[serde(default = "default_feature_bin_num")]
+    pub feature_bin_num: usize,
+}
+
+impl Default for StatisticsConfig {
+    fn default() -> Self {
+        Self {
+            percentiles: default_percentiles(),
+            histogram_bin_num: default_histogram_bin_num(),
+            time_limit: None,
+            feature_bin_num: default_feature_bin_num(),
+        }
+    }
+}
+
+/// Default value of `percentiles`.
+fn default_percentiles() -> Vec<f64> {
+    vec![5.0, 25.0, 75.0, 95.0]
+}
+
+/// Default value of `histogram_bin_num`.
+fn default_histogram_bin_num() -> usize {
+    20
+}
+
+/// Default value of `feature_bin_num`.
+fn default_feature_bin_num() -> usize {
+    4
+}
+
+impl StatisticsConfig {
+    /// Validates the percentiles and the numbers of bins.
+    fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.percentiles
+                .iter()
+                .all(|percentile| (0.0..=100.0).contains(percentile)),
+            "`statistics.percentiles` must be in the range from 0 to 100."
+        );
+        ensure!(
+            self.histogram_bin_num != 0,
+            "`statistics.histogram_bin_num` must be positive."
+        );
+        ensure!(
+            self.feature_bin_num != 0,
+            "`statistics.feature_bin_num` must be positive."
+        );
+
+        Ok(())
+    }
+}
+
+/// Rule for extracting a named feature (a numeric parameter of the problem) from each input file.
+///
+/// The feature is read either from the position given by `line` and `column`, or with `pattern`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeatureConfig {
+    /// Name of the feature.
+    pub name: String,
+
+    /// Line (0-indexed) of the input file on which the feature is written.
+    pub line: Option<usize>,
+
+    /// Position (0-indexed) of the feature among the whitespace-separated values on the line.
+    #[serde(default)]
+    pub column: usize,
+
+    /// Regular expression for retrieving the feature.
+    /// The value must be captured by the named group `value`.
+    pub pattern: Option<String>,
+}
+
+/// Validates the names and the locations of the features.
+fn validate_features(features: &[FeatureConfig]) -> anyhow::Result<()> {
+    for (i, feature) in features.iter().enumerate() {
+        ensure!(!feature.name.is_empty(), "Feature name must be non-empty.");
+        ensure!(
+            features[..i].iter().all(|other| other.name != feature.name),
+            "Feature `{}` is defined more than once.",
+            feature.name
+        );
+        ensure!(
+            feature.line.is_some() != feature.pattern.is_some(),
+            "Exactly one of `line` and `pattern` must be specified for feature `{}`.",
+            feature.name
+        );
+    }
+
+    Ok(())
+}
+
+/// Rule for extracting a named metric (a numeric value reported by the submission code) from the standard error output.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MetricConfig {
+    /// Name of the metric.
+    pub name: String,
+
+    /// Regular expression for retrieving the metric.
+    /// The value must be captured by the named group `value`.
+    /// If it matches several times, the last match is used.
+    pub pattern: String,
+}
+
+/// Validates the names of the metrics.
+fn validate_metrics(metrics: &[MetricConfig]) -> anyhow::Result<()> {
+    for (i, metric) in metrics.iter().enumerate() {
+        ensure!(!metric.name.is_empty(), "Metric name must be non-empty.");
+        ensure!(
+            metrics[..i].iter().all(|other| other.name != metric.name),
+            "Metric `{}` is defined more than once.",
+            metric.name
+        );
+    }
+
+    Ok(())
+}
+
+/// Value of a parameter written in the configuration file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum ParamValue {
+    Integer(i64),
+    Float(f64),
+    String(String),
+}
+
+impl std::fmt::Display for ParamValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Integer(value) => write!(f, "{}", value),
+            Self::Float(value) => write!(f, "{}", value),
+            Self::String(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Parameter of the submission code, which is varied by `sweep` and `tune`.
+///
+/// The candidate values are given either by `values`, or by the range from `min` to `max`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ParamConfig {
+    /// Name of the parameter, which is exported as the environment variable `AHC_PARAM_NAME`.
+    pub name: String,
+
+    /// Candidate values of the parameter.
+    pub values: Option<Vec<ParamValue>>,
+
+    /// Minimum value of the parameter.
+    pub min: Option<f64>,
+
+    /// Maximum value of the parameter.
+    pub max: Option<f64>,
+
+    /// Interval between the candidate values from `min` to `max`.
+    /// It is required by `sweep`, and `tune` rounds the values to it if specified.
+    pub step: Option<f64>,
+
+    /// Set this flag to `true` to search the range from `min` to `max` on a logarithmic scale in `tune`.
+    #[serde(default)]
+    pub log: bool,
+}
+
+/// Validates the names and the candidate values of the parameters.
+fn validate_params(params: &[ParamConfig]) -> anyhow::Result<()> {
+    for (i, param) in params.iter().enumerate() {
+        ensure!(
+            params[..i].iter().all(|other| other.name != param.name),
+            "Parameter `{}` is defined more than once.",
+            param.name
+        );
+        param.validate()?;
+    }
+
+    Ok(())
+}
+
+impl ParamConfig {
+    /// Validates the name and the candidate values of the parameter.
+    fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && self.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'),
+            "Parameter name `{}` must consist of alphanumeric characters and `_`, and must not start with a digit.",
+            self.name
+        );
+
+        match (&self.values, self.min, self.max) {
+            (Some(values), None, None) => ensure!(
+                !values.is_empty() && self.step.is_none() && !self.log,
+                "`values` of parameter `{}` must not be empty and cannot be combined with `step` or `log`.",
+                self.name
+            ),
+            (None, Some(min), Some(max)) => {
+                ensure!(
+                    min.is_finite() && max.is_finite() && min <= max,
+                    "`min` and `max` of parameter `{}` must be finite numbers with `min <= max`.",
+                    self.name
+                );
+                ensure!(
+                    self.step.is_none_or(|step| step.is_finite() && step > 0.0),
+                    "`step` of parameter `{}` must be a positive number.",
+                    self.name
+                );
+                ensure!(
+                    !self.log || min > 0.0,
+                    "`min` of parameter `{}` must be positive if `log` is `true`.",
+                    self.name
+                );
+            }
+            _ => bail!(
+                "Either `values`, or `min` and `max` must be specified for parameter `{}`.",
+                self.name
+            ),
+        }
+
+        Ok(())
+    }
+
+    /// Returns the candidate values of the parameter as strings, or `None` if the range has no `step`.
+    pub fn grid_values(&self) -> Option<Vec<String>> {
+        if let Some(values) = &self.values {
+            return Some(values.iter().map(ParamValue::to_string).collect());
+        }
+
+        let step = self.step?;
+
+        Some(
+            (0..=self.max_step_index(step))
+                .map(|i| self.format_value(self.min.unwrap() + step * i as f64))
+                .collect(),
+        )
+    }
+
+    /// Returns the value at the position `u` in `[0, 1]` of the range from `min` to `max`,
+    /// rounded to a multiple of `step` from `min` if `step` is specified.
+    pub fn value_at(&self, u: f64) -> String {
+        let (min, max) = (self.min.unwrap(), self.max.unwrap());
+
+        let value = if self.log {
+            (min.ln() + u * (max.ln() - min.ln())).exp()
+        } else {
+            min + u * (max - min)
+        };
+
+        match self.step {
+            Some(step) => {
+                let i = ((value - min) / step).round().max(0.0) as usize;
+                self.format_value(min + step * i.min(self.max_step_index(step)) as f64)
+            }
+            None => self.format_value(value),
+        }
+    }
+
+    /// Returns the value used when the parameter is not varied, which is the first candidate value or `min`.
+    pub fn initial_value(&self) -> String {
+        match &self.values {
+            Some(values) => values[0].to_string(),
+            None => self.format_value(self.min.unwrap()),
+        }
+    }
+
+    /// Returns the number of steps from `min` to the largest candidate value not exceeding `max`.
+    fn max_step_index(&self, step: f64) -> usize {
+        // A small margin keeps `max` against rounding errors.
+        ((self.max.unwrap() - self.min.unwrap()) / step + 1e-9).floor() as usize
+    }
+
+    /// Formats the value in the range, as an integer if both `min` and `step` are integers.
+    fn format_value(&self, value: f64) -> String {
+        let integer =
+            self.min.unwrap().fract() == 0.0 && self.step.is_some_and(|step| step.fract() == 0.0);
+
+        if integer {
+            format!("{}", value.round() as i64)
+        } else {
+            // Rounds the value to 6 significant digits to hide rounding errors.
+            format!("{}", format!("{:.5e}", value).parse::<f64>().unwrap())
+        }
+    }
+}
+
+/// Search method of `tune`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TuneMethod {
+    /// Values are sampled uniformly at random.
+    Random,
+
+    /// Values are sampled at random, and the better candidates are evaluated on more seeds.
+    SuccessiveHalving,
+
+    /// Values are sampled by the Tree-structured Parzen Estimator after random trials.
+    #[default]
+    Tpe,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TuneConfig {
+    /// Search method.
+    #[serde(default)]
+    pub method: TuneMethod,
+
+    /// Number of trials, which is the number of initial candidates in successive halving.
+    #[serde(default = "default_trial_num")]
+    pub trial_num: usize,
+
+    /// Seed of the random numbers, which makes the search reproducible.
+    #[serde(default)]
+    pub seed: u64,
+
+    /// Path of the file in which the result of every trial is saved.
+    /// The evaluations saved in it are reused, so an interrupted search can be resumed.
+    #[serde(default = "default_trial_file")]
+    pub trial_file: PathBuf,
+
+    /// Path of the CSV file to which the evaluation records of the best parameters are written.
+    #[serde(default = "default_best_record")]
+    pub best_record: PathBuf,
+
+    /// Number of random trials before the Tree-structured Parzen Estimator is used.
+    #[serde(default = "default_startup_trial_num")]
+    pub startup_trial_num: usize,
+
+    /// Factor by which the candidates are reduced and the seeds are increased in each round of successive halving.
+    #[serde(default = "default_reduction_factor")]
+    pub reduction_factor: usize,
+
+    /// Number of seeds in the first round of successive halving.
+    /// If not specified, it is determined so that the last candidate is evaluated on all seeds.
+    pub initial_seed_num: Option<usize>,
+}
+
+impl Default for TuneConfig {
+    fn default() -> Self {
+        Self {
+            method: TuneMethod::default(),
+            trial_num: default_trial_num(),
+            seed: 0,
+            trial_file: default_trial_file(),
+            best_record: default_best_record(),
+            startup_trial_num: default_startup_trial_num(),
+            reduction_factor: default_reduction_factor(),
+            initial_seed_num: None,
+        }
+    }
+}
+
+/// Default value of `trial_num`.
+fn default_trial_num() -> usize {
+    30
+}
+
+/// Default value of `trial_file`.
+fn default_trial_file() -> PathBuf {
+    PathBuf::from("evaluation/trials.jsonl")
+}
+
+/// Default value of `best_record`.
+fn default_best_record() -> PathBuf {
+    PathBuf::from("evaluation/tune_best.csv")
+}
+
+/// Default value of `startup_trial_num`.
+fn default_startup_trial_num() -> usize {
+    10
+}
+
+/// Default value of `reduction_factor`.
+fn default_reduction_factor() -> usize {
+    3
+}
+
+impl TuneConfig {
+    /// Validates the numbers of trials and seeds.
+    fn validate(&self) -> anyhow::Result<()> {
+        ensure!(self.trial_num != 0, "`tune.trial_num` must be positive.");
+        ensure!(
+            self.reduction_factor >= 2,
+            "`tune.reduction_factor` must be at least 2."
+        );
+        ensure!(
+            self.initial_seed_num != Some(0),
+            "`tune.initial_seed_num` must be positive."
+        );
+
+        Ok(())
+    }
+}
+
+/// Compression of the outputs saved as artifacts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Compression {
+    /// The outputs are saved as they are.
+    #[default]
+    None,
+
+    /// The outputs are compressed with gzip and saved with the extension `.gz`.
+    Gzip,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ArtifactConfig {
+    /// Path pattern of the directory in which the artifacts of each seed are saved.
+    /// The placeholder `{seed}` must be used, with a width such as `{seed:04}` if necessary.
+    /// The directory is cleared before the artifacts of the seed are saved.
+    pub dir: String,
+
+    /// Maximum size in KiB of each saved output.
+    /// The rest of the output is omitted.
+    #[serde(default = "default_max_output_size")]
+    pub max_output_size: u64,
+
+    /// Compression of the saved outputs.
+    #[serde(default)]
+    pub compression: Compression,
+}
+
+/// Default value of `max_output_size`.
+fn default_max_output_size() -> u64 {
+    1024
+}
+
+impl ArtifactConfig {
+    /// Validates the pattern of the directory, which must differ for each seed since it is cleared before saving.
+    fn validate(&self) -> anyhow::Result<()> {
+        template::validate(&self.dir, &["seed"])
+            .with_context(|| "Invalid pattern in `artifact.dir`.")?;
+
+        ensure!(
+            expand_seed_pattern(&self.dir, 0) != expand_seed_pattern(&self.dir, 1),
+            "`artifact.dir` must contain the placeholder `{{seed}}`."
+        );
+
+        Ok(())
+    }
+}
+
+/// Rule for collecting the progress of the score over time from the standard error output.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProgressConfig {
+    /// Regular expression for retrieving a progress line.
+    /// The elapsed time in seconds and the score must be captured by the named groups `time` and `score`.
+    /// Lines whose time or score cannot be parsed as a finite number are skipped with a warning.
+    #[serde(default = "default_progress_pattern")]
+    pub pattern: String,
+
+    /// Number of points in time at which the aggregate curve is shown.
+    #[serde(default = "default_point_num")]
+    pub point_num: usize,
+}
+
+/// Regular expression matching a decimal number such as `12`, `-0.5` or `1.2e-3`.
+const NUMBER_PATTERN: &str = r"[-+]?(?:[0-9]+(?:\.[0-9]*)?|\.[0-9]+)(?:[eE][-+]?[0-9]+)?";
+
+/// Default value of `pattern` in `ProgressConfig`.
+fn default_progress_pattern() -> String {
+    format!(
+        r"\bprogress +t *= *(?<time>{0}) +score *= *(?<score>{0})",
+        NUMBER_PATTERN
+    )
+}
+
+/// Default value of `point_num`.
+fn default_point_num() -> usize {
+    10
+}
+
+impl ProgressConfig {
+    /// Validates the number of points.
+    fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.point_num != 0,
+            "`progress.point_num` must be positive."
+        );
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -74,6 +992,52 @@ pub struct Config {
 
     /// Configuration of command line arguments.
     pub command: CommandConfig,
+
+    /// Configuration of score retrieval.
+    #[serde(default)]
+    pub score: ScoreConfig,
+
+    /// Configuration of the statistics shown after evaluation.
+    #[serde(default)]
+    pub statistics: StatisticsConfig,
+
+    /// Features extracted from each input file.
+    #[serde(default, rename = "feature")]
+    pub features: Vec<FeatureConfig>,
+
+    /// Metrics extracted from the standard error output of each seed.
+    #[serde(default, rename = "metric")]
+    pub metrics: Vec<MetricConfig>,
+
+    /// Parameters of the submission code.
+    #[serde(default, rename = "param")]
+    pub params: Vec<ParamConfig>,
+
+    /// Configuration of the parameter search by `tune`.
+    #[serde(default)]
+    pub tune: TuneConfig,
+
+    /// Configuration of the artifacts saved for each seed.
+    /// If not specified, the artifacts are not saved.
+    pub artifact: Option<ArtifactConfig>,
+
+    /// Configuration of the progress collected for each seed.
+    /// If not specified, the progress is not collected.
+    pub progress: Option<ProgressConfig>,
+
+    /// Values of the parameters passed to the execution commands.
+    /// The initial value of each parameter is used unless `sweep` or `tune` changes them.
+    #[serde(skip)]
+    pub param_values: Vec<(String, String)>,
+
+    /// Identifier of the current run, which is set when the evaluation starts.
+    #[serde(skip)]
+    pub run_id: Option<String>,
+
+    /// Name of the subdirectory in the artifact directory of each seed,
+    /// which is set by `sweep` to keep the artifacts of each combination of the parameters.
+    #[serde(skip)]
+    pub artifact_subdir: Option<String>,
 }
 
 impl Config {
@@ -84,31 +1048,448 @@ impl Config {
     {
         let config_str = read_to_string(config_file_path)
             .with_context(|| "Failed to read configuration file.")?;
-        toml::from_str(&config_str).with_context(|| "Failed to deserialize configuration file.")
+        let mut config: Self = toml::from_str(&config_str)
+            .with_context(|| "Failed to deserialize configuration file.")?;
+
+        // Validates each section.
+        config.path.validate()?;
+        config.command.validate(&config.params)?;
+        config.score.validate()?;
+        config.statistics.validate()?;
+        validate_features(&config.features)?;
+        validate_metrics(&config.metrics)?;
+        validate_params(&config.params)?;
+        config.tune.validate()?;
+        if let Some(artifact) = &config.artifact {
+            artifact.validate()?;
+        }
+        if let Some(progress) = &config.progress {
+            progress.validate()?;
+        }
+
+        config.param_values = config
+            .params
+            .iter()
+            .map(|param| (param.name.clone(), param.initial_value()))
+            .collect();
+
+        Ok(config)
     }
 
     /// Returns the path to the input file.
     pub fn input_file_path(&self, seed: usize) -> PathBuf {
-        self.path.input_dir.join(format!("{:04}.txt", seed))
+        self.path
+            .input_dir
+            .join(expand_seed_pattern(&self.path.input_file, seed))
     }
 
     /// Returns the path to the output file.
     pub fn output_file_path(&self, seed: usize) -> PathBuf {
-        self.path.output_dir.join(format!("{:04}.txt", seed))
+        self.path
+            .output_dir
+            .join(expand_seed_pattern(&self.path.output_file, seed))
     }
 
-    /// Returns the command to execute the local tester with placeholders replaced.
-    pub fn cmd_args_for_execute_tester(&self, seed: usize) -> Vec<String> {
+    /// Returns the path to the file in which the standard error output of the submission code is saved.
+    pub fn stderr_file_path(&self, seed: usize) -> Option<PathBuf> {
+        let pattern = self.path.stderr_file.as_ref()?;
+
+        Some(PathBuf::from(expand_seed_pattern(pattern, seed)))
+    }
+
+    /// Returns the path to the file in which the standard error output of the local tester is saved.
+    pub fn log_file_path(&self, seed: usize) -> Option<PathBuf> {
+        let pattern = self.path.log_file.as_ref()?;
+
+        Some(PathBuf::from(expand_seed_pattern(pattern, seed)))
+    }
+
+    /// Returns the path to the file in which the full output of the failed command is saved.
+    pub fn failure_log_file_path(&self, seed: usize) -> Option<PathBuf> {
+        let pattern = self.path.failure_log_file.as_ref()?;
+
+        Some(PathBuf::from(expand_seed_pattern(pattern, seed)))
+    }
+
+    /// Returns the path to the directory in which the artifacts of the seed are saved.
+    pub fn artifact_dir_path(&self, seed: usize) -> Option<PathBuf> {
+        let pattern = &self.artifact.as_ref()?.dir;
+        let dir = PathBuf::from(expand_seed_pattern(pattern, seed));
+
+        Some(match &self.artifact_subdir {
+            Some(subdir) => dir.join(subdir),
+            None => dir,
+        })
+    }
+
+    /// Returns the output of a command decoded lossily, with the middle omitted if it is too long.
+    pub fn output_excerpt(&self, output: &[u8]) -> String {
+        excerpt::excerpt(
+            output,
+            self.command.error_output_head,
+            self.command.error_output_tail,
+        )
+    }
+
+    /// Returns the time limit for each seed.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.command.execute.timeout.map(Duration::from_secs_f64)
+    }
+
+    /// Returns the time to wait after `SIGTERM` before `SIGKILL` is sent.
+    pub fn kill_grace_period(&self) -> Duration {
+        Duration::from_secs_f64(self.command.execute.kill_grace_period)
+    }
+
+    /// Returns the path to the file from which the score is retrieved.
+    pub fn score_file_path(&self, seed: usize) -> Option<PathBuf> {
+        let file = self.score.file.as_ref()?;
+        let path = template::expand(file, |name| match name {
+            "seed" => Some(seed.to_string()),
+            "input" => Some(self.input_file_path(seed).to_str().unwrap().to_owned()),
+            "output" => Some(self.output_file_path(seed).to_str().unwrap().to_owned()),
+            _ => None,
+        })
+        .unwrap();
+
+        Some(PathBuf::from(path))
+    }
+
+    /// Returns the memory limit in bytes.
+    pub fn memory_limit(&self) -> Option<u64> {
+        self.command
+            .execute
+            .memory_limit
+            .map(|memory_limit| memory_limit * 1024 * 1024)
+    }
+
+    /// Returns the threshold of the peak resident set size in bytes.
+    pub fn rss_limit(&self) -> Option<u64> {
         self.command
             .execute
-            .tester
+            .rss_limit
+            .map(|rss_limit| rss_limit * 1024 * 1024)
+    }
+
+    /// Returns the value of the placeholder, or `None` if the name is unknown.
+    ///
+    /// The placeholders specific to the seed are available only if `seed` is specified.
+    /// If `absolute` is `true`, the paths are made absolute.
+    fn placeholder(&self, name: &str, seed: Option<usize>, absolute: bool) -> Option<String> {
+        match (name, seed) {
+            ("input-dir", _) => Some(path_to_string(&self.path.input_dir, absolute)),
+            ("output-dir", _) => Some(path_to_string(&self.path.output_dir, absolute)),
+            ("run-id", _) => Some(self.run_id.clone().unwrap_or_default()),
+            ("seed", Some(seed)) => Some(seed.to_string()),
+            ("input" | "input-path", Some(seed)) => {
+                Some(path_to_string(&self.input_file_path(seed), absolute))
+            }
+            ("output" | "output-path", Some(seed)) => {
+                Some(path_to_string(&self.output_file_path(seed), absolute))
+            }
+            (name, Some(_)) => {
+                let param_name = name.strip_prefix("param.")?;
+                self.param_values
+                    .iter()
+                    .find(|(name, _)| name == param_name)
+                    .map(|(_, value)| value.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the command to build the submission code with placeholders replaced.
+    pub fn command_for_build_submission(&self) -> CommandSpec {
+        let build = &self.command.build;
+
+        self.command_spec(
+            &build.submission,
+            &build.submission_env,
+            build.submission_cwd.as_deref(),
+            None,
+            |_, _| None,
+        )
+    }
+
+    /// Returns the command to build the local tester with placeholders replaced.
+    pub fn command_for_build_tester(&self) -> CommandSpec {
+        let build = &self.command.build;
+
+        self.command_spec(
+            &build.tester,
+            &build.tester_env,
+            build.tester_cwd.as_deref(),
+            None,
+            |_, _| None,
+        )
+    }
+
+    /// Returns the command to execute the submission code with placeholders replaced.
+    pub fn command_for_execute_submission(&self, seed: usize) -> CommandSpec {
+        let execute = &self.command.execute;
+
+        self.command_spec(
+            &execute.submission,
+            &execute.submission_env,
+            execute.submission_cwd.as_deref(),
+            Some(seed),
+            |_, _| None,
+        )
+    }
+
+    /// Returns the command to execute the local tester with placeholders replaced.
+    pub fn command_for_execute_tester(&self, seed: usize) -> CommandSpec {
+        let execute = &self.command.execute;
+
+        self.command_spec(
+            &execute.tester,
+            &execute.tester_env,
+            execute.tester_cwd.as_deref(),
+            Some(seed),
+            |name, absolute| match name {
+                "cmd" | "submission-execute" => {
+                    let mut cmd_args = expand_cmd_args(&execute.submission, |name| {
+                        self.placeholder(name, Some(seed), absolute)
+                    });
+
+                    // Makes the path of the program absolute since the local tester runs in another directory.
+                    if let Some(program) = cmd_args.first_mut().filter(|_| absolute) {
+                        if program.contains('/') {
+                            *program = path_to_string(Path::new(program), true);
+                        }
+                    }
+
+                    // Limits the address space of the submission code in the shell since the local tester is not limited.
+                    if let Some(memory_limit) = self.memory_limit() {
+                        let script = format!("ulimit -v {} && exec \"$@\"", memory_limit / 1024);
+                        let wrapper = ["sh".to_owned(), "-c".to_owned(), script, "sh".to_owned()];
+                        cmd_args.splice(0..0, wrapper);
+                    }
+
+                    Some(
+                        cmd_args
+                            .iter()
+                            .map(|arg| template::shell_quote(arg))
+                            .join(" "),
+                    )
+                }
+                _ => None,
+            },
+        )
+    }
+
+    /// Returns the command to generate the input file of the seed with placeholders replaced.
+    ///
+    /// `params` are the parameters written on the line of the seed in the seed file.
+    ///
+    /// # Errors
+    ///
+    /// If a parameter used in the command is not written on the line of the seed, an error is generated.
+    pub fn command_for_generate(
+        &self,
+        seed: usize,
+        params: &[(String, String)],
+    ) -> anyhow::Result<CommandSpec> {
+        let command = &self.command;
+        let cmd_args = command.generate.as_deref().unwrap_or_default();
+        let lookup = |name: &str| {
+            let param_name = name.strip_prefix("param.")?;
+            params
+                .iter()
+                .find(|(name, _)| name == param_name)
+                .map(|(_, value)| value.clone())
+        };
+
+        // Checks the parameters, since the other placeholders have been validated when the configuration was read.
+        for template in cmd_args
             .iter()
-            .map(|arg| match arg.as_str() {
-                "{input}" => self.input_file_path(seed).to_str().unwrap().to_owned(),
-                "{output}" => self.output_file_path(seed).to_str().unwrap().to_owned(),
-                "{cmd}" => self.command.execute.submission.iter().join(" "),
-                _ => arg.clone(),
+            .chain(command.generate_env.values())
+            .chain(&command.generate_cwd)
+        {
+            template::expand(template, |name| {
+                if name.starts_with("param.") {
+                    lookup(name)
+                } else {
+                    Some(String::new())
+                }
             })
-            .collect()
+            .with_context(|| format!("Failed to expand the generate command for seed {}.", seed))?;
+        }
+
+        Ok(self.command_spec(
+            cmd_args,
+            &command.generate_env,
+            command.generate_cwd.as_deref(),
+            Some(seed),
+            |name, _| lookup(name),
+        ))
+    }
+
+    /// Returns the command to generate the input files of all the missing seeds at once with placeholders replaced.
+    pub fn command_for_generate_batch(
+        &self,
+        seeds_file_path: &Path,
+        gen_dir: &Path,
+    ) -> CommandSpec {
+        let command = &self.command;
+
+        self.command_spec(
+            command.generate.as_deref().unwrap_or_default(),
+            &command.generate_env,
+            command.generate_cwd.as_deref(),
+            None,
+            |name, absolute| match name {
+                "seeds-file" => Some(path_to_string(seeds_file_path, absolute)),
+                "gen-dir" => Some(path_to_string(gen_dir, absolute)),
+                _ => None,
+            },
+        )
+    }
+
+    /// Replaces the placeholders in the command line arguments, the environment variables and the working directory,
+    /// and adds the environment variables exported automatically.
+    ///
+    /// `lookup` returns the values of the placeholders specific to the command.
+    fn command_spec<F>(
+        &self,
+        cmd_args: &[String],
+        env: &BTreeMap<String, String>,
+        cwd: Option<&str>,
+        seed: Option<usize>,
+        lookup: F,
+    ) -> CommandSpec
+    where
+        F: Fn(&str, bool) -> Option<String>,
+    {
+        // Makes the paths absolute if the command runs in another directory.
+        let absolute = cwd.is_some();
+        let lookup =
+            |name: &str| lookup(name, absolute).or_else(|| self.placeholder(name, seed, absolute));
+
+        let mut envs = vec![(
+            "AHC_RUN_ID".to_owned(),
+            self.run_id.clone().unwrap_or_default(),
+        )];
+        if let Some(seed) = seed {
+            envs.push(("AHC_SEED".to_owned(), seed.to_string()));
+            envs.push((
+                "AHC_INPUT".to_owned(),
+                path_to_string(&self.input_file_path(seed), true),
+            ));
+            envs.push((
+                "AHC_OUTPUT".to_owned(),
+                path_to_string(&self.output_file_path(seed), true),
+            ));
+            envs.extend(
+                self.param_values
+                    .iter()
+                    .map(|(name, value)| (format!("{}{}", PARAM_ENV_PREFIX, name), value.clone())),
+            );
+        }
+        envs.extend(
+            env.iter()
+                .map(|(name, value)| (name.clone(), template::expand(value, lookup).unwrap())),
+        );
+
+        CommandSpec {
+            cmd_args: expand_cmd_args(cmd_args, lookup),
+            envs,
+            cwd: cwd.map(|cwd| PathBuf::from(template::expand(cwd, lookup).unwrap())),
+        }
     }
 }
+
+/// Command with the placeholders replaced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandSpec {
+    /// Command line arguments.
+    pub cmd_args: Vec<String>,
+
+    /// Environment variables set in addition to those inherited from this process.
+    pub envs: Vec<(String, String)>,
+
+    /// Working directory, or `None` to use the current directory.
+    pub cwd: Option<PathBuf>,
+}
+
+impl CommandSpec {
+    /// Creates a `Command` with the arguments, the environment variables and the working directory.
+    ///
+    /// # Errors
+    ///
+    /// If the command line arguments are empty, an error is generated.
+    pub fn to_command(&self) -> anyhow::Result<Command> {
+        let program = self
+            .cmd_args
+            .first()
+            .with_context(|| "The execution command is empty.")?;
+
+        let mut command = Command::new(program);
+        command.args(&self.cmd_args[1..]).envs(self.envs.clone());
+
+        if let Some(cwd) = &self.cwd {
+            command.current_dir(cwd);
+        }
+
+        Ok(command)
+    }
+}
+
+/// Prefix of the environment variables to which the parameters are exported.
+const PARAM_ENV_PREFIX: &str = "AHC_PARAM_";
+
+/// Placeholders available in the build commands.
+const BUILD_PLACEHOLDERS: &[&str] = &["input-dir", "output-dir", "run-id"];
+
+/// Placeholders available in the command to execute the submission code.
+const SEED_PLACEHOLDERS: &[&str] = &[
+    "input-dir",
+    "output-dir",
+    "run-id",
+    "seed",
+    "input",
+    "input-path",
+    "output",
+    "output-path",
+];
+
+/// Placeholders available in the command to execute the local tester.
+const TESTER_PLACEHOLDERS: &[&str] = &[
+    "input-dir",
+    "output-dir",
+    "run-id",
+    "seed",
+    "input",
+    "input-path",
+    "output",
+    "output-path",
+    "cmd",
+    "submission-execute",
+];
+
+/// Expands the placeholders in the command, which have been validated when the configuration was read.
+fn expand_cmd_args<F>(cmd_args: &[String], lookup: F) -> Vec<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    cmd_args
+        .iter()
+        .map(|arg| template::expand(arg, &lookup).unwrap())
+        .collect()
+}
+
+/// Expands the placeholder `{seed}` in the pattern, which has been validated when the configuration was read.
+fn expand_seed_pattern(pattern: &str, seed: usize) -> String {
+    template::expand(pattern, |name| (name == "seed").then(|| seed.to_string())).unwrap()
+}
+
+/// Converts the path into a string, making it absolute if `absolute` is `true`.
+fn path_to_string(path: &Path, absolute: bool) -> String {
+    let path = if absolute {
+        std::path::absolute(path).unwrap_or_else(|_| path.to_owned())
+    } else {
+        path.to_owned()
+    };
+
+    path.to_str().unwrap().to_owned()
+}
@@ -0,0 +1,456 @@
+//! Provides the collection of the artifacts saved for each seed.
+
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, read_to_string, remove_dir_all, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use flate2::{write::GzEncoder, Compression as GzCompression};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{ArtifactConfig, CommandSpec, Compression, Config},
+    evaluation::{process::ProcessOutput, EvaluationRecord, Program},
+};
+
+/// Name of the metadata file in the artifact directory.
+const METADATA_FILE_NAME: &str = "metadata.json";
+
+/// Command executed during the evaluation of the seed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CommandArtifact {
+    /// Program executed by the command (`submission` or `tester`).
+    program: String,
+
+    /// Command line arguments.
+    cmd_args: Vec<String>,
+
+    /// Environment variables set in addition to those inherited.
+    envs: BTreeMap<String, String>,
+
+    /// Working directory, or `None` if it is the current directory.
+    cwd: Option<PathBuf>,
+
+    /// Exit status of the process, or `None` if the process was not waited for.
+    exit_status: Option<String>,
+
+    /// Whether the process was killed because it exceeded the time limit.
+    timed_out: bool,
+
+    /// Elapsed time in seconds.
+    execution_time: Option<f64>,
+
+    /// CPU time in seconds spent in user mode.
+    user_time: Option<f64>,
+
+    /// CPU time in seconds spent in kernel mode.
+    system_time: Option<f64>,
+
+    /// Peak resident set size in KiB.
+    peak_memory: Option<u64>,
+}
+
+/// Contents of the metadata file.
+#[derive(Debug, Serialize, Deserialize)]
+struct Metadata {
+    seed: usize,
+    commands: Vec<CommandArtifact>,
+
+    /// Evaluation record, which includes the verdict, the execution time and the memory usage.
+    record: EvaluationRecord,
+}
+
+/// Command lines and outputs of the processes collected during the evaluation of a seed.
+///
+/// Nothing is collected if the artifacts are disabled.
+#[derive(Debug)]
+pub struct Artifacts<'a> {
+    /// Configuration of the artifacts, or `None` if disabled.
+    artifact_config: Option<&'a ArtifactConfig>,
+
+    /// Commands in the order of execution.
+    commands: Vec<CommandArtifact>,
+
+    /// File names and contents of the outputs, which are truncated to the size limit.
+    outputs: Vec<(String, Vec<u8>)>,
+}
+
+impl<'a> Artifacts<'a> {
+    /// Creates an empty collection of the artifacts according to the configuration.
+    pub fn new(config: &'a Config) -> Self {
+        Self {
+            artifact_config: config.artifact.as_ref(),
+            commands: vec![],
+            outputs: vec![],
+        }
+    }
+
+    /// Records the command executed for the program.
+    pub fn add_command(&mut self, program: Program, command_spec: &CommandSpec) {
+        if self.artifact_config.is_none() {
+            return;
+        }
+
+        self.commands.push(CommandArtifact {
+            program: program_name(program).to_owned(),
+            cmd_args: command_spec.cmd_args.clone(),
+            envs: command_spec.envs.iter().cloned().collect(),
+            cwd: command_spec.cwd.clone(),
+            exit_status: None,
+            timed_out: false,
+            execution_time: None,
+            user_time: None,
+            system_time: None,
+            peak_memory: None,
+        });
+    }
+
+    /// Records the result of the process of the program recorded last.
+    ///
+    /// The standard error output is always recorded, and the standard output is recorded only if `with_stdout` is `true`.
+    pub fn add_process_output(
+        &mut self,
+        program: Program,
+        process_output: &ProcessOutput,
+        with_stdout: bool,
+    ) {
+        let Some(artifact_config) = self.artifact_config else {
+            return;
+        };
+
+        if let Some(command) = self
+            .commands
+            .iter_mut()
+            .rev()
+            .find(|command| command.program == program_name(program))
+        {
+            let resource_usage = &process_output.resource_usage;
+
+            command.exit_status = Some(process_output.output.status.to_string());
+            command.timed_out = process_output.timed_out;
+            command.execution_time = Some(process_output.execution_time);
+            command.user_time = Some(resource_usage.user_time);
+            command.system_time = Some(resource_usage.system_time);
+            command.peak_memory = Some(resource_usage.peak_memory);
+        }
+
+        let limit = (artifact_config.max_output_size * 1024) as usize;
+        let mut streams = vec![("stderr", &process_output.output.stderr)];
+        if with_stdout {
+            streams.insert(0, ("stdout", &process_output.output.stdout));
+        }
+
+        for (stream, contents) in streams {
+            let file_name = format!("{}_{}.txt", program_name(program), stream);
+            self.outputs.push((file_name, truncate(contents, limit)));
+        }
+    }
+
+    /// Saves the collected artifacts and the evaluation record to the artifact directory of the seed.
+    ///
+    /// The artifacts left by a previous evaluation of the seed are removed.
+    pub fn save(
+        &self,
+        config: &Config,
+        seed: usize,
+        record: &EvaluationRecord,
+    ) -> anyhow::Result<()> {
+        let (Some(artifact_config), Some(dir)) =
+            (self.artifact_config, config.artifact_dir_path(seed))
+        else {
+            return Ok(());
+        };
+
+        if dir.exists() {
+            remove_dir_all(&dir)
+                .with_context(|| format!("Failed to remove artifact directory `{:?}`.", dir))?;
+        }
+        create_dir_all(&dir)
+            .with_context(|| format!("Failed to create artifact directory `{:?}`.", dir))?;
+
+        // Saves the outputs, compressing them if specified.
+        for (file_name, contents) in &self.outputs {
+            save_output(&dir, file_name, contents, artifact_config.compression)?;
+        }
+
+        // Saves the command lines and the evaluation record.
+        let metadata = Metadata {
+            seed,
+            commands: self.commands.clone(),
+            record: record.clone(),
+        };
+        save_metadata(&dir.join(METADATA_FILE_NAME), &metadata)
+    }
+}
+
+/// Replaces the evaluation records in the metadata files with those finalized after the evaluation,
+/// such as the records with the relative scores.
+///
+/// The records of the seeds without a metadata file are ignored.
+pub fn update_artifact_records(
+    config: &Config,
+    evaluation_table: &[EvaluationRecord],
+) -> anyhow::Result<()> {
+    for record in evaluation_table {
+        let Some(dir) = config.artifact_dir_path(record.seed) else {
+            return Ok(());
+        };
+
+        let file_path = dir.join(METADATA_FILE_NAME);
+        if !file_path.exists() {
+            continue;
+        }
+
+        let json = read_to_string(&file_path)
+            .with_context(|| format!("Failed to read metadata file `{:?}`.", file_path))?;
+        let mut metadata: Metadata = serde_json::from_str(&json)
+            .with_context(|| format!("Failed to deserialize metadata file `{:?}`.", file_path))?;
+
+        metadata.record = record.clone();
+        save_metadata(&file_path, &metadata)?;
+    }
+
+    Ok(())
+}
+
+/// Saves the metadata to the file.
+fn save_metadata(file_path: &Path, metadata: &Metadata) -> anyhow::Result<()> {
+    let file = File::create(file_path)
+        .with_context(|| format!("Failed to create metadata file `{:?}`.", file_path))?;
+
+    serde_json::to_writer_pretty(file, metadata)
+        .with_context(|| format!("Failed to write to `{:?}`.", file_path))
+}
+
+/// Returns the name of the program used in the artifacts.
+fn program_name(program: Program) -> &'static str {
+    match program {
+        Program::Submission => "submission",
+        Program::Tester => "tester",
+    }
+}
+
+/// Returns the first `limit` bytes of the contents, followed by a note if the rest is omitted.
+fn truncate(contents: &[u8], limit: usize) -> Vec<u8> {
+    if contents.len() <= limit {
+        return contents.to_vec();
+    }
+
+    let mut truncated = contents[..limit].to_vec();
+    truncated.extend_from_slice(
+        format!("\n... ({} bytes omitted)\n", contents.len() - limit).as_bytes(),
+    );
+
+    truncated
+}
+
+/// Saves the output to the file in the directory with the compression.
+fn save_output(
+    dir: &Path,
+    file_name: &str,
+    contents: &[u8],
+    compression: Compression,
+) -> anyhow::Result<()> {
+    let file_path = match compression {
+        Compression::None => dir.join(file_name),
+        Compression::Gzip => dir.join(format!("{}.gz", file_name)),
+    };
+
+    let file = File::create(&file_path)
+        .with_context(|| format!("Failed to create artifact file `{:?}`.", file_path))?;
+
+    match compression {
+        Compression::None => (&file).write_all(contents),
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(file, GzCompression::default());
+            encoder
+                .write_all(contents)
+                .and_then(|_| encoder.finish().map(|_| ()))
+        }
+    }
+    .with_context(|| format!("Failed to write to `{:?}`.", file_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        fs::{read, write},
+        io::Read,
+        os::unix::process::ExitStatusExt,
+        process::{self, ExitStatus, Output},
+    };
+
+    use flate2::read::GzDecoder;
+
+    use super::*;
+    use crate::evaluation::process::ResourceUsage;
+
+    /// Returns the default configuration with the artifacts saved in a temporary directory.
+    fn config_with_artifact(name: &str, compression: &str) -> Config {
+        let mut config: Config = toml::from_str(include_str!("../../config.toml")).unwrap();
+        let dir = env::temp_dir().join(format!(
+            "ahc-evaluation-artifact-test-{}-{}",
+            process::id(),
+            name
+        ));
+        config.artifact = Some(
+            toml::from_str(&format!(
+                "dir = '{}/{{seed}}'\nmax_output_size = 1\ncompression = \"{}\"",
+                dir.to_str().unwrap(),
+                compression
+            ))
+            .unwrap(),
+        );
+        config
+    }
+
+    fn process_output(stdout: &[u8], stderr: &[u8]) -> ProcessOutput {
+        ProcessOutput {
+            output: Output {
+                status: ExitStatus::from_raw(0),
+                stdout: stdout.to_vec(),
+                stderr: stderr.to_vec(),
+            },
+            execution_time: 1.5,
+            timed_out: false,
+            resource_usage: ResourceUsage {
+                user_time: 1.25,
+                system_time: 0.25,
+                peak_memory: 2048,
+            },
+        }
+    }
+
+    fn record() -> EvaluationRecord {
+        EvaluationRecord::accepted(0, 100.0, 1.5, &process_output(b"", b"").resource_usage)
+    }
+
+    /// Collects the artifacts of the submission code and the local tester.
+    fn artifacts(config: &Config) -> Artifacts<'_> {
+        let command_spec = |program: &str| CommandSpec {
+            cmd_args: vec![program.to_owned(), "0000.txt".to_owned()],
+            envs: vec![("AHC_SEED".to_owned(), "0".to_owned())],
+            cwd: None,
+        };
+
+        let mut artifacts = Artifacts::new(config);
+        artifacts.add_command(Program::Submission, &command_spec("./a.out"));
+        artifacts.add_process_output(
+            Program::Submission,
+            &process_output(b"ignored", b"submission log\n"),
+            false,
+        );
+        artifacts.add_command(Program::Tester, &command_spec("./tester"));
+        artifacts.add_process_output(
+            Program::Tester,
+            &process_output(b"Score = 100\n", &[b'x'; 2000]),
+            true,
+        );
+        artifacts
+    }
+
+    fn read_metadata(dir: &Path) -> Metadata {
+        let json = read_to_string(dir.join(METADATA_FILE_NAME)).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn save_outputs_and_metadata() {
+        let config = config_with_artifact("plain", "none");
+        let dir = config.artifact_dir_path(0).unwrap();
+
+        // The artifacts of a previous evaluation are removed.
+        create_dir_all(&dir).unwrap();
+        write(dir.join("stale.txt"), "stale").unwrap();
+
+        let mut record = record();
+        artifacts(&config).save(&config, 0, &record).unwrap();
+
+        let stale_exists = dir.join("stale.txt").exists();
+        let submission_stdout_exists = dir.join("submission_stdout.txt").exists();
+        let submission_stderr = read_to_string(dir.join("submission_stderr.txt")).unwrap();
+        let tester_stdout = read_to_string(dir.join("tester_stdout.txt")).unwrap();
+        let tester_stderr = read_to_string(dir.join("tester_stderr.txt")).unwrap();
+        let metadata = read_metadata(&dir);
+
+        // The records in the metadata are replaced with the finalized ones.
+        record.relative_score = Some(0.5);
+        update_artifact_records(&config, &[record]).unwrap();
+        let updated_metadata = read_metadata(&dir);
+
+        remove_dir_all(dir.parent().unwrap()).unwrap();
+
+        assert!(!stale_exists);
+        assert!(!submission_stdout_exists);
+        assert_eq!(submission_stderr, "submission log\n");
+        assert_eq!(tester_stdout, "Score = 100\n");
+
+        // The output is truncated to `max_output_size`.
+        assert_eq!(
+            tester_stderr,
+            "x".repeat(1024) + "\n... (976 bytes omitted)\n"
+        );
+
+        assert_eq!(metadata.seed, 0);
+        assert_eq!(metadata.commands.len(), 2);
+        let tester = &metadata.commands[1];
+        assert_eq!(tester.program, "tester");
+        assert_eq!(tester.cmd_args, ["./tester", "0000.txt"]);
+        assert_eq!(
+            tester.envs,
+            BTreeMap::from([("AHC_SEED".to_owned(), "0".to_owned())])
+        );
+        assert_eq!(tester.exit_status.as_deref(), Some("exit status: 0"));
+        assert_eq!(tester.execution_time, Some(1.5));
+        assert_eq!(tester.peak_memory, Some(2048));
+        assert_eq!(metadata.record.score, Some(100.0));
+        assert_eq!(metadata.record.relative_score, None);
+
+        assert_eq!(updated_metadata.commands.len(), 2);
+        assert_eq!(updated_metadata.record.relative_score, Some(0.5));
+    }
+
+    #[test]
+    fn save_outputs_with_gzip() {
+        let config = config_with_artifact("gzip", "gzip");
+        let dir = config.artifact_dir_path(0).unwrap();
+
+        let record = record();
+        artifacts(&config).save(&config, 0, &record).unwrap();
+
+        let plain_exists = dir.join("tester_stdout.txt").exists();
+        let compressed = read(dir.join("tester_stdout.txt.gz")).unwrap();
+        let metadata_exists = dir.join(METADATA_FILE_NAME).exists();
+        remove_dir_all(dir.parent().unwrap()).unwrap();
+
+        let mut decompressed = String::new();
+        GzDecoder::new(&compressed[..])
+            .read_to_string(&mut decompressed)
+            .unwrap();
+
+        assert!(!plain_exists);
+        assert_eq!(decompressed, "Score = 100\n");
+
+        // The metadata is not compressed.
+        assert!(metadata_exists);
+    }
+
+    #[test]
+    fn collect_nothing_if_disabled() {
+        let mut config = config_with_artifact("disabled", "none");
+        let dir = config.artifact_dir_path(0).unwrap();
+        config.artifact = None;
+
+        let artifacts = artifacts(&config);
+        let record = record();
+        artifacts.save(&config, 0, &record).unwrap();
+
+        assert!(artifacts.commands.is_empty());
+        assert!(artifacts.outputs.is_empty());
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,233 @@
+//! Provides a persistent table of the best known score for each seed.
+
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::Context;
+use itertools::Itertools;
+
+use crate::{
+    config::Objective,
+    evaluation::record::{EvaluationRecord, ScoreValue},
+};
+
+/// Best known score for a seed, as stored in the file.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+struct BestScoreRecord {
+    seed: usize,
+    #[serde(serialize_with = "serialize_score_value")]
+    score: f64,
+}
+
+/// Serializes the best score, writing integral values without a fractional part.
+fn serialize_score_value<S>(score: &f64, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serde::Serialize::serialize(&ScoreValue(*score), serializer)
+}
+
+/// Best known score for each seed.
+#[derive(Debug, Clone, Default)]
+pub struct BestScoreTable {
+    /// Mapping from seeds to their best known scores.
+    best_scores: BTreeMap<usize, f64>,
+}
+
+impl BestScoreTable {
+    /// Reads the table from the CSV file.
+    ///
+    /// If the file does not exist, an empty table is returned.
+    pub fn read_from_file<P>(file_path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let file_path = file_path.as_ref();
+
+        if !file_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let mut reader = csv::Reader::from_path(file_path)
+            .with_context(|| format!("Failed to open best score file `{:?}`.", file_path))?;
+
+        let best_scores = reader
+            .deserialize::<BestScoreRecord>()
+            .map_ok(|record| (record.seed, record.score))
+            .collect::<Result<BTreeMap<_, _>, _>>()
+            .with_context(|| format!("Failed to read best score file `{:?}`.", file_path))?;
+
+        Ok(Self { best_scores })
+    }
+
+    /// Writes the table to the CSV file.
+    pub fn write_to_file<P>(&self, file_path: P) -> anyhow::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let file_path = file_path.as_ref();
+
+        let mut writer = csv::Writer::from_path(file_path)
+            .with_context(|| format!("Failed to open best score file `{:?}`.", file_path))?;
+
+        for (&seed, &score) in &self.best_scores {
+            writer
+                .serialize(BestScoreRecord { seed, score })
+                .with_context(|| "Failed to serialize the best score.")?;
+        }
+
+        writer
+            .flush()
+            .with_context(|| "Failed to write best scores to file.")?;
+
+        Ok(())
+    }
+
+    /// Returns the best known score for the seed.
+    pub fn get(&self, seed: usize) -> Option<f64> {
+        self.best_scores.get(&seed).copied()
+    }
+
+    /// Updates the best known score for the seed if the score is better.
+    ///
+    /// Returns `true` if the best known score is updated.
+    pub fn update(&mut self, seed: usize, score: f64, objective: Objective) -> bool {
+        match self.best_scores.get_mut(&seed) {
+            Some(best_score) if !is_better(score, *best_score, objective) => false,
+            Some(best_score) => {
+                *best_score = score;
+                true
+            }
+            None => {
+                self.best_scores.insert(seed, score);
+                true
+            }
+        }
+    }
+}
+
+/// Returns `true` if `score` is strictly better than `other`.
+pub fn is_better(score: f64, other: f64, objective: Objective) -> bool {
+    match objective {
+        Objective::Maximize => score > other,
+        Objective::Minimize => score < other,
+    }
+}
+
+/// Returns the relative score of `score` against `best_score`.
+///
+/// For maximization problems it is `score / best_score`, and for minimization problems it is `best_score / score`,
+/// so that the best score is 1 and worse scores approach 0.
+/// Scores are assumed to be non-negative.
+pub fn relative_score(score: f64, best_score: f64, objective: Objective) -> f64 {
+    let (numerator, denominator) = match objective {
+        Objective::Maximize => (score, best_score),
+        Objective::Minimize => (best_score, score),
+    };
+
+    if denominator == 0.0 {
+        return if numerator == 0.0 { 1.0 } else { 0.0 };
+    }
+
+    numerator / denominator
+}
+
+/// Updates the best score file with the accepted records and sets their relative scores.
+pub fn update_best_scores<P>(
+    best_score_file_path: P,
+    objective: Objective,
+    evaluation_table: &mut [EvaluationRecord],
+) -> anyhow::Result<()>
+where
+    P: AsRef<Path>,
+{
+    let best_score_table =
+        calculate_relative_scores(&best_score_file_path, objective, evaluation_table)?;
+
+    best_score_table.write_to_file(&best_score_file_path)
+}
+
+/// Sets the relative scores of the accepted records against the best scores including them,
+/// leaving the best score file unchanged, and returns the updated best scores.
+pub fn calculate_relative_scores<P>(
+    best_score_file_path: P,
+    objective: Objective,
+    evaluation_table: &mut [EvaluationRecord],
+) -> anyhow::Result<BestScoreTable>
+where
+    P: AsRef<Path>,
+{
+    let mut best_score_table = BestScoreTable::read_from_file(&best_score_file_path)?;
+
+    for record in evaluation_table.iter() {
+        if let Some(score) = record.score {
+            best_score_table.update(record.seed, score, objective);
+        }
+    }
+
+    for record in evaluation_table.iter_mut() {
+        record.relative_score = record.score.map(|score| {
+            relative_score(score, best_score_table.get(record.seed).unwrap(), objective)
+        });
+    }
+
+    Ok(best_score_table)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    #[test]
+    fn relative_score_for_each_objective() {
+        assert_eq!(relative_score(50.0, 200.0, Objective::Maximize), 0.25);
+        assert_eq!(relative_score(200.0, 200.0, Objective::Maximize), 1.0);
+        assert_eq!(relative_score(200.0, 50.0, Objective::Minimize), 0.25);
+        assert_eq!(relative_score(50.0, 50.0, Objective::Minimize), 1.0);
+    }
+
+    #[test]
+    fn relative_score_with_zero_scores() {
+        assert_eq!(relative_score(0.0, 0.0, Objective::Maximize), 1.0);
+        assert_eq!(relative_score(5.0, 0.0, Objective::Maximize), 0.0);
+        assert_eq!(relative_score(0.0, 0.0, Objective::Minimize), 1.0);
+        assert_eq!(relative_score(0.0, 5.0, Objective::Minimize), 0.0);
+    }
+
+    #[test]
+    fn update_only_with_better_scores() {
+        let mut table = BestScoreTable::default();
+
+        assert!(table.update(0, 10.0, Objective::Maximize));
+        assert!(!table.update(0, 10.0, Objective::Maximize));
+        assert!(!table.update(0, 5.0, Objective::Maximize));
+        assert!(table.update(0, 20.0, Objective::Maximize));
+        assert_eq!(table.get(0), Some(20.0));
+        assert_eq!(table.get(1), None);
+
+        assert!(table.update(1, 10.0, Objective::Minimize));
+        assert!(table.update(1, 5.0, Objective::Minimize));
+        assert_eq!(table.get(1), Some(5.0));
+    }
+
+    #[test]
+    fn read_written_table() {
+        let file_path =
+            env::temp_dir().join(format!("ahc-evaluation-best-score-{}.csv", process::id()));
+
+        let mut table = BestScoreTable::default();
+        table.update(3, 1234.0, Objective::Maximize);
+        table.update(7, 0.5, Objective::Maximize);
+        table.write_to_file(&file_path).unwrap();
+
+        let text = fs::read_to_string(&file_path).unwrap();
+        let read_table = BestScoreTable::read_from_file(&file_path);
+        fs::remove_file(&file_path).unwrap();
+
+        assert_eq!(text, "seed,score\n3,1234\n7,0.5\n");
+        let read_table = read_table.unwrap();
+        assert_eq!(read_table.get(3), Some(1234.0));
+        assert_eq!(read_table.get(7), Some(0.5));
+    }
+}
@@ -0,0 +1,118 @@
+//! Provides a structure to extract the features of the problem from each input file.
+
+use std::{collections::BTreeMap, fs::read, path::Path};
+
+use anyhow::{ensure, Context};
+use regex::Regex;
+
+use crate::config::FeatureConfig;
+
+/// Method of locating a feature in the input file.
+#[derive(Debug, Clone)]
+enum FeatureRule {
+    /// The feature is the whitespace-separated value at the position.
+    Position { line: usize, column: usize },
+
+    /// The feature is captured by the named group `value` of the regular expression.
+    Pattern(Regex),
+}
+
+/// Extracts the features according to the feature configurations.
+#[derive(Debug, Clone)]
+pub struct FeatureExtractor {
+    /// Name and rule of each feature.
+    rules: Vec<(String, FeatureRule)>,
+}
+
+impl FeatureExtractor {
+    /// Compiles the regular expressions in the feature configurations.
+    pub fn new(feature_configs: &[FeatureConfig]) -> anyhow::Result<Self> {
+        let mut rules = vec![];
+
+        for feature_config in feature_configs {
+            let rule = match (&feature_config.pattern, feature_config.line) {
+                (Some(pattern), _) => {
+                    let regex = Regex::new(pattern).with_context(|| {
+                        format!("Failed to compile regular expression `{}`.", pattern)
+                    })?;
+
+                    ensure!(
+                        regex.capture_names().flatten().any(|name| name == "value"),
+                        "The regular expression `{}` does not have the named group `value`.",
+                        pattern
+                    );
+
+                    FeatureRule::Pattern(regex)
+                }
+                (None, Some(line)) => FeatureRule::Position {
+                    line,
+                    column: feature_config.column,
+                },
+                (None, None) => unreachable!(),
+            };
+
+            rules.push((feature_config.name.clone(), rule));
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// Extracts the features from the input file.
+    ///
+    /// # Errors
+    ///
+    /// If a feature is not found or cannot be parsed as a number, an error is generated.
+    pub fn extract<P>(&self, input_file_path: P) -> anyhow::Result<BTreeMap<String, f64>>
+    where
+        P: AsRef<Path>,
+    {
+        let input_file_path = input_file_path.as_ref();
+
+        if self.rules.is_empty() {
+            return Ok(BTreeMap::new());
+        }
+
+        // Decodes the input file lossily so that it does not need to be valid UTF-8.
+        let input_bytes = read(input_file_path)
+            .with_context(|| format!("Failed to read input file `{:?}`.", input_file_path))?;
+        let input_text = String::from_utf8_lossy(&input_bytes);
+
+        let mut features = BTreeMap::new();
+
+        for (name, rule) in &self.rules {
+            let value = match rule {
+                FeatureRule::Position { line, column } => input_text
+                    .lines()
+                    .nth(*line)
+                    .and_then(|line| line.split_whitespace().nth(*column)),
+                FeatureRule::Pattern(regex) => regex
+                    .captures(&input_text)
+                    .and_then(|caps| caps.name("value"))
+                    .map(|value| value.as_str().trim()),
+            }
+            .with_context(|| {
+                format!(
+                    "Feature `{}` was not found in `{:?}`.",
+                    name, input_file_path
+                )
+            })?;
+
+            let value = value.parse::<f64>().with_context(|| {
+                format!(
+                    "Failed to parse `{}` as the value of feature `{}`.",
+                    value, name
+                )
+            })?;
+            ensure!(
+                value.is_finite(),
+                "The value `{}` of feature `{}` is not finite.",
+                value,
+                name
+            );
+
+            features.insert(name.clone(), value);
+        }
+
+        Ok(features)
+    }
+}
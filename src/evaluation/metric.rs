@@ -0,0 +1,154 @@
+//! Provides a structure to extract the metrics reported by the submission code from the standard error output.
+
+use std::collections::BTreeMap;
+
+use anyhow::{ensure, Context};
+use regex::Regex;
+
+use crate::config::MetricConfig;
+
+/// Extracts the metrics according to the metric configurations.
+#[derive(Debug, Clone)]
+pub struct MetricExtractor {
+    /// Name and regular expression of each metric.
+    rules: Vec<(String, Regex)>,
+}
+
+impl MetricExtractor {
+    /// Compiles the regular expressions in the metric configurations.
+    pub fn new(metric_configs: &[MetricConfig]) -> anyhow::Result<Self> {
+        let mut rules = vec![];
+
+        for metric_config in metric_configs {
+            let pattern = &metric_config.pattern;
+            let regex = Regex::new(pattern)
+                .with_context(|| format!("Failed to compile regular expression `{}`.", pattern))?;
+
+            ensure!(
+                regex.capture_names().flatten().any(|name| name == "value"),
+                "The regular expression `{}` does not have the named group `value`.",
+                pattern
+            );
+
+            rules.push((metric_config.name.clone(), regex));
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// Extracts the metrics from the outputs, which are searched in order of precedence.
+    ///
+    /// Each metric is taken from the first output in which it matches, such as the standard error output of the submission code
+    /// before that of the local tester.
+    /// If a metric matches several times in the output, the last match is used, and the metrics not found are omitted.
+    /// A metric that cannot be parsed as a finite number is also omitted with a warning.
+    pub fn extract(&self, outputs: &[&[u8]], seed: usize) -> BTreeMap<String, f64> {
+        if self.rules.is_empty() {
+            return BTreeMap::new();
+        }
+
+        // Decodes the outputs lossily since they may not be valid UTF-8.
+        let texts = outputs
+            .iter()
+            .map(|output| String::from_utf8_lossy(output))
+            .collect::<Vec<_>>();
+
+        let mut metrics = BTreeMap::new();
+
+        for (name, regex) in &self.rules {
+            let Some(value) = texts
+                .iter()
+                .find_map(|text| regex.captures_iter(text).last())
+                .and_then(|caps| caps.name("value"))
+                .map(|value| value.as_str().trim())
+            else {
+                continue;
+            };
+
+            match value.parse::<f64>() {
+                Ok(value) if value.is_finite() => {
+                    metrics.insert(name.clone(), value);
+                }
+                _ => eprintln!(
+                    "Warning: Failed to parse `{}` as the value of metric `{}` for seed {}.",
+                    value, name, seed
+                ),
+            }
+        }
+
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extractor(rules: &[(&str, &str)]) -> MetricExtractor {
+        let metric_configs = rules
+            .iter()
+            .map(|&(name, pattern)| MetricConfig {
+                name: name.to_owned(),
+                pattern: pattern.to_owned(),
+            })
+            .collect::<Vec<_>>();
+
+        MetricExtractor::new(&metric_configs).unwrap()
+    }
+
+    #[test]
+    fn extract_last_match() {
+        let extractor = extractor(&[
+            ("iterations", r"iterations = (?<value>\S+)"),
+            ("temp", r"temp = (?<value>\S+)"),
+            ("missing", r"missing = (?<value>\S+)"),
+        ]);
+        let stderr = b"iterations = 10\ntemp = 1.5e-3\niterations = 20\n";
+
+        let metrics = extractor.extract(&[stderr], 0);
+
+        assert_eq!(
+            metrics,
+            BTreeMap::from([("iterations".to_owned(), 20.0), ("temp".to_owned(), 1.5e-3)])
+        );
+    }
+
+    #[test]
+    fn omit_non_finite_value() {
+        let extractor = extractor(&[
+            ("inf", r"inf = (?<value>\S+)"),
+            ("nan", r"nan = (?<value>\S+)"),
+            ("text", r"text = (?<value>\S+)"),
+        ]);
+        let stderr = b"inf = inf\nnan = NaN\ntext = abc\n";
+
+        assert!(extractor.extract(&[stderr], 0).is_empty());
+    }
+
+    #[test]
+    fn prefer_earlier_output() {
+        let extractor = extractor(&[
+            ("iterations", r"iterations = (?<value>\S+)"),
+            ("turns", r"turns = (?<value>\S+)"),
+        ]);
+        let submission_stderr = b"iterations = 100\n";
+        let tester_stderr = b"iterations = 5\nturns = 7\n";
+
+        let metrics = extractor.extract(&[submission_stderr, tester_stderr], 0);
+
+        assert_eq!(
+            metrics,
+            BTreeMap::from([("iterations".to_owned(), 100.0), ("turns".to_owned(), 7.0)])
+        );
+    }
+
+    #[test]
+    fn reject_pattern_without_value_group() {
+        let metric_configs = [MetricConfig {
+            name: "iterations".to_owned(),
+            pattern: r"iterations = (\S+)".to_owned(),
+        }];
+
+        assert!(MetricExtractor::new(&metric_configs).is_err());
+    }
+}
@@ -1,26 +1,57 @@
+mod artifact;
+mod best_score;
+mod feature;
+mod metric;
+mod process;
+mod progress;
 mod record;
+mod score;
 mod stop_watch;
 
 use std::{
-    fs::{create_dir_all, read_to_string, File},
+    collections::BTreeMap,
+    fs::{create_dir_all, write, File},
     io::Write,
-    process::{Command, Stdio},
+    path::PathBuf,
+    process::{ExitStatus, Output, Stdio},
 };
 
-use anyhow::{bail, ensure, Context};
+use anyhow::Context;
 use indicatif::{ParallelProgressIterator, ProgressBar, ProgressStyle};
 use rayon::{iter::IntoParallelRefIterator, prelude::ParallelIterator};
-use regex::Regex;
 
 use crate::{
     config::Config,
-    evaluation::{record::EvaluationRecord, stop_watch::Stopwatch},
+    evaluation::{
+        artifact::Artifacts,
+        feature::FeatureExtractor,
+        metric::MetricExtractor,
+        process::{
+            exceeded_memory_limit, spawn_process, wait_with_timeout, ProcessOutput, ResourceUsage,
+        },
+        progress::ProgressExtractor,
+        score::ScoreExtractor,
+        stop_watch::Stopwatch,
+    },
+    seed::SeedEntry,
 };
 
-pub use crate::evaluation::record::{show_statistics, write_to_csv};
+pub use crate::evaluation::{
+    artifact::update_artifact_records,
+    best_score::{
+        calculate_relative_scores, is_better, relative_score, update_best_scores, BestScoreTable,
+    },
+    record::{
+        read_from_csv, show_failures, show_progress_curve, show_statistics, show_sweep_statistics,
+        write_to_csv, EvaluationRecord, Status,
+    },
+};
 
 /// Executes the submission code and the local tester for each seed and collect the score and the execution time.
-pub fn evaluate(config: &Config, seeds: &[usize]) -> anyhow::Result<Vec<EvaluationRecord>> {
+pub fn evaluate(
+    config: &Config,
+    seed_entries: &[SeedEntry],
+) -> anyhow::Result<Vec<EvaluationRecord>> {
     // Style of progress bar.
     let progress_style = ProgressStyle::template(
         ProgressStyle::default_bar(),
@@ -29,7 +60,7 @@ pub fn evaluate(config: &Config, seeds: &[usize]) -> anyhow::Result<Vec<Evaluati
     .with_context(|| "Failed to create progress bar style.")?;
 
     // Progress bar during running of the local tester.
-    let progress_bar = ProgressBar::new(seeds.len() as u64);
+    let progress_bar = ProgressBar::new(seed_entries.len() as u64);
     progress_bar.set_style(progress_style);
     progress_bar.set_prefix("Running...");
 
@@ -37,56 +68,187 @@ pub fn evaluate(config: &Config, seeds: &[usize]) -> anyhow::Result<Vec<Evaluati
     create_dir_all(&config.path.output_dir)
         .with_context(|| "Failed to create output directory.")?;
 
+    // Compiles the rule for retrieving the score.
+    let score_extractor = ScoreExtractor::new(&config.score)?;
+
+    // Compiles the rules for extracting the features of the inputs.
+    let feature_extractor = FeatureExtractor::new(&config.features)?;
+
+    // Compiles the rules for extracting the metrics from the standard error outputs.
+    let metric_extractor = MetricExtractor::new(&config.metrics)?;
+
+    // Compiles the rule for collecting the progress from the standard error outputs.
+    let progress_extractor = ProgressExtractor::new(config.progress.as_ref())?;
+
     // Executes the local tester and retrieve evaluations.
-    seeds
+    seed_entries
         .par_iter()
         .progress_with(progress_bar)
-        .map(|&seed| {
+        .map(|seed_entry| {
+            evaluate_seed(
+                config,
+                &score_extractor,
+                &feature_extractor,
+                &metric_extractor,
+                &progress_extractor,
+                seed_entry,
+            )
+        })
+        .collect::<Result<Vec<EvaluationRecord>, _>>()
+}
+
+/// Extracts the features of the input and evaluates the submission code for the seed.
+///
+/// This is the only place where a failure is recorded as a verdict in keep-going mode,
+/// so the tags and the features extracted before the failure are kept in the record.
+fn evaluate_seed(
+    config: &Config,
+    score_extractor: &ScoreExtractor,
+    feature_extractor: &FeatureExtractor,
+    metric_extractor: &MetricExtractor,
+    progress_extractor: &ProgressExtractor,
+    seed_entry: &SeedEntry,
+) -> anyhow::Result<EvaluationRecord> {
+    let seed = seed_entry.seed;
+    let mut artifacts = Artifacts::new(config);
+    let mut features = BTreeMap::new();
+
+    let result = feature_extractor
+        .extract(config.input_file_path(seed))
+        .and_then(|extracted| {
+            features = extracted;
+
+            // Removes the score file left by a previous evaluation.
+            score_extractor.remove_score_file(config, seed)?;
+
             if config.command.execute.integrated {
-                execute_integrated_process(config, seed)
+                execute_integrated_process(
+                    config,
+                    score_extractor,
+                    metric_extractor,
+                    progress_extractor,
+                    seed,
+                    &mut artifacts,
+                )
             } else {
-                execute_independent_processes(config, seed)
+                execute_independent_processes(
+                    config,
+                    score_extractor,
+                    metric_extractor,
+                    progress_extractor,
+                    seed,
+                    &mut artifacts,
+                )
             }
-        })
-        .collect::<Result<Vec<EvaluationRecord>, _>>()
+        });
+    let result = recover_from_error(config, seed, result).map(|record| EvaluationRecord {
+        tags: seed_entry.tags.clone(),
+        features,
+        ..record
+    });
+
+    // Saves the artifacts with the verdict even if the evaluation failed.
+    match &result {
+        Ok(record) => artifacts.save(config, seed, record)?,
+        Err(err) => {
+            let record = EvaluationRecord {
+                tags: seed_entry.tags.clone(),
+                ..EvaluationRecord::from_error(seed, err)
+            };
+            artifacts.save(config, seed, &record)?
+        }
+    }
+
+    result
 }
 
-/// Executes the submission code via the local tester.
-fn execute_integrated_process(config: &Config, seed: usize) -> anyhow::Result<EvaluationRecord> {
-    // Reads the input file.
+/// Records the failure as a verdict for the seed instead of aborting the evaluation in keep-going mode.
+fn recover_from_error(
+    config: &Config,
+    seed: usize,
+    result: anyhow::Result<EvaluationRecord>,
+) -> anyhow::Result<EvaluationRecord> {
+    match result {
+        Err(err) if config.command.execute.keep_going => {
+            Ok(EvaluationRecord::from_error(seed, &err))
+        }
+        result => result,
+    }
+}
+
+/// Opens the input file of the seed for reading.
+fn open_input_file(config: &Config, seed: usize) -> anyhow::Result<File> {
     let input_file_path = config.input_file_path(seed);
-    let input_text = read_to_string(&input_file_path)
-        .with_context(|| format!("Failed to read text from `{:?}`.", input_file_path))?;
+
+    File::open(&input_file_path)
+        .with_context(|| format!("Failed to open input file `{:?}`.", input_file_path))
+}
+
+/// Executes the submission code via the local tester.
+fn execute_integrated_process(
+    config: &Config,
+    score_extractor: &ScoreExtractor,
+    metric_extractor: &MetricExtractor,
+    progress_extractor: &ProgressExtractor,
+    seed: usize,
+    artifacts: &mut Artifacts,
+) -> anyhow::Result<EvaluationRecord> {
+    // Opens the input file, which is passed to the standard input directly.
+    let input_file = open_input_file(config, seed)?;
 
     // Executes the local tester as a child process.
-    let cmd_args = config.cmd_args_for_execute_tester(seed);
-    let process_handle = spawn_process(&cmd_args)?;
+    let command_spec = config.command_for_execute_tester(seed);
+    let cmd_args = command_spec.cmd_args.clone();
+    artifacts.add_command(Program::Tester, &command_spec);
+    // The memory limit is applied to the submission code by `{cmd}`, not to the local tester.
+    let process_handle = spawn_process(&command_spec, input_file.into(), None)?;
 
     // Starts measuring execution time.
     let stopwatch = Stopwatch::start();
 
-    // Writes the contents of the input file to the standard input.
-    process_handle
-        .stdin
-        .as_ref()
-        .unwrap()
-        .write_all(input_text.as_bytes())
-        .with_context(|| "Failed to input text.")?;
-
     // Waits for process to terminate.
-    let output = process_handle.wait_with_output()?;
+    let process_output = wait_with_timeout(
+        process_handle,
+        stopwatch,
+        config.timeout(),
+        config.kill_grace_period(),
+    )?;
+
+    // Saves the standard error output of the local tester, which includes that of the submission code,
+    // to the log file, or to the stderr file if the log file is not specified.
+    let stderr_file_path = config
+        .log_file_path(seed)
+        .or_else(|| config.stderr_file_path(seed));
+    save_stream(stderr_file_path, &process_output.output.stderr)?;
+    artifacts.add_process_output(Program::Tester, &process_output, true);
+
+    let execution_time = process_output.execution_time;
+    let resource_usage = process_output.resource_usage;
+
+    if let Some((status, failure)) = check_limits(config, Program::Tester, &process_output) {
+        return Ok(EvaluationRecord::rejected(
+            seed,
+            status,
+            failure,
+            execution_time,
+            &resource_usage,
+        ));
+    }
+
+    let output = process_output.output;
 
-    ensure!(
-        output.status.success(),
-        ExecuteCommandError {
+    if !output.status.success() && score_extractor.requires_success() {
+        return Err(ExecuteCommandError::new(
+            config,
             seed,
+            Program::Tester,
             cmd_args,
-            output
-        }
-    );
-
-    // Terminates measurement of execution time.
-    let execution_time = stopwatch.elapsed_time();
+            &output,
+            execution_time,
+            resource_usage,
+        )?
+        .into());
+    }
 
     // Writes the contents of the standard output to the output file.
     let output_file_path = config.output_file_path(seed);
@@ -95,85 +257,101 @@ fn execute_integrated_process(config: &Config, seed: usize) -> anyhow::Result<Ev
         .write_all(&output.stdout)
         .with_context(|| format!("Failed to write to output file {:?}.", output_file_path))?;
 
-    // Regular expression for retrieving a score.
-    let score_regex = Regex::new(r"\bScore *= *(?<score>[0-9]*)\b")
-        .with_context(|| "Failed to compile regular expression.")?;
-
-    let stderr = String::from_utf8(output.stderr.clone())?;
-
     // Retrieve the score from the output of the local tester.
-    let Some(score) = score_regex
-        .captures(&stderr)
-        .and_then(|caps| caps["score"].parse::<i64>().ok())
-    else {
-        bail!(format!(
-            "
-Failed to retrieve score.
-
-Seed: {}
-
----------------------------- Standard Error Output -----------------------------
-{:?}
---------------------------------------------------------------------------------
-",
-            seed, output.stderr,
-        ));
+    let Some(score) = score_extractor.extract(config, seed, &output)? else {
+        return Err(ScoreNotFoundError::new(config, seed, &cmd_args, &output, false)?.into());
     };
 
+    // Retrieves the metrics and the progress from the standard error output of the local tester,
+    // which includes that of the submission code.
+    let metrics = metric_extractor.extract(&[&output.stderr], seed);
+    let progress = progress_extractor.extract(&[&output.stderr], seed);
+
     Ok(EvaluationRecord {
-        seed,
-        score,
-        execution_time,
+        metrics,
+        progress,
+        ..EvaluationRecord::accepted(seed, score, execution_time, &resource_usage)
     })
 }
 
 /// Executes the submission code and the local tester separately.
-fn execute_independent_processes(config: &Config, seed: usize) -> anyhow::Result<EvaluationRecord> {
-    // Reads the input file.
-    let input_file_path = config.input_file_path(seed);
-    let input_text = read_to_string(&input_file_path)
-        .with_context(|| format!("Failed to read text from `{:?}`.", input_file_path))?;
+fn execute_independent_processes(
+    config: &Config,
+    score_extractor: &ScoreExtractor,
+    metric_extractor: &MetricExtractor,
+    progress_extractor: &ProgressExtractor,
+    seed: usize,
+    artifacts: &mut Artifacts,
+) -> anyhow::Result<EvaluationRecord> {
+    // Opens the input file, which is passed to the standard input directly.
+    let input_file = open_input_file(config, seed)?;
 
     // Executes the submission code as a child process.
-    let cmd_args_for_execute_submission = &config.command.execute.submission;
-    let submission_process_handle = spawn_process(cmd_args_for_execute_submission)?;
+    let command_spec_for_execute_submission = config.command_for_execute_submission(seed);
+    let cmd_args_for_execute_submission = &command_spec_for_execute_submission.cmd_args;
+    artifacts.add_command(Program::Submission, &command_spec_for_execute_submission);
+    let submission_process_handle = spawn_process(
+        &command_spec_for_execute_submission,
+        input_file.into(),
+        config.memory_limit(),
+    )?;
 
     // Starts measuring execution time.
     let stopwatch = Stopwatch::start();
 
-    // Writes the contents of the input file to the standard input.
-    submission_process_handle
-        .stdin
-        .as_ref()
-        .unwrap()
-        .write_all(input_text.as_bytes())
-        .with_context(|| "Failed to input text.")?;
-
     // Waits for process to terminate.
-    let submission_process_output =
-        submission_process_handle
-            .wait_with_output()
-            .with_context(|| {
-                format!(
-                    "
+    let submission_process_output = wait_with_timeout(
+        submission_process_handle,
+        stopwatch,
+        config.timeout(),
+        config.kill_grace_period(),
+    )
+    .with_context(|| {
+        format!(
+            "
 Failed to execute the submission code.
 List of arguments: {:?}
 ",
-                    cmd_args_for_execute_submission
-                )
-            })?;
+            cmd_args_for_execute_submission
+        )
+    })?;
+
+    // Saves the standard error output of the submission code.
+    save_stream(
+        config.stderr_file_path(seed),
+        &submission_process_output.output.stderr,
+    )?;
+    artifacts.add_process_output(Program::Submission, &submission_process_output, false);
+
+    let execution_time = submission_process_output.execution_time;
+    let resource_usage = submission_process_output.resource_usage;
+
+    if let Some((status, failure)) =
+        check_limits(config, Program::Submission, &submission_process_output)
+    {
+        return Ok(EvaluationRecord::rejected(
+            seed,
+            status,
+            failure,
+            execution_time,
+            &resource_usage,
+        ));
+    }
 
-    // Terminates measurement of execution time.
-    let execution_time = stopwatch.elapsed_time();
+    let submission_process_output = submission_process_output.output;
 
-    ensure!(
-        submission_process_output.status.success(),
-        ExecuteCommandError {
+    if !submission_process_output.status.success() {
+        return Err(ExecuteCommandError::new(
+            config,
             seed,
-            cmd_args: cmd_args_for_execute_submission.to_owned(),
-            output: submission_process_output
-        }
-    );
+            Program::Submission,
+            cmd_args_for_execute_submission.to_owned(),
+            &submission_process_output,
+            execution_time,
+            resource_usage,
+        )?
+        .into());
+    }
 
     // Writes the contents of the standard output to the output file.
     let output_file_path = config.output_file_path(seed);
@@ -183,110 +361,251 @@ List of arguments: {:?}
         .with_context(|| format!("Failed to write to output file {:?}.", output_file_path))?;
 
     // Executes the local tester as a child process.
-    let cmd_args_for_execute_tester = config.cmd_args_for_execute_tester(seed);
+    let command_spec_for_execute_tester = config.command_for_execute_tester(seed);
+    let cmd_args_for_execute_tester = command_spec_for_execute_tester.cmd_args.clone();
+    artifacts.add_command(Program::Tester, &command_spec_for_execute_tester);
 
     // Waits for process to terminate.
-    let tester_process_output = spawn_process(&cmd_args_for_execute_tester)?
-        .wait_with_output()
-        .with_context(|| {
-            format!(
-                "
+    let tester_process_output = wait_with_timeout(
+        spawn_process(&command_spec_for_execute_tester, Stdio::null(), None)?,
+        Stopwatch::start(),
+        config.timeout(),
+        config.kill_grace_period(),
+    )
+    .with_context(|| {
+        format!(
+            "
 Failed to execute the local tester.
 List of arguments: {:?}
 ",
-                cmd_args_for_execute_tester
-            )
-        })?;
-
-    ensure!(
-        tester_process_output.status.success(),
-        ExecuteCommandError {
+            cmd_args_for_execute_tester
+        )
+    })?;
+
+    // Saves the standard error output of the local tester.
+    save_stream(
+        config.log_file_path(seed),
+        &tester_process_output.output.stderr,
+    )?;
+    artifacts.add_process_output(Program::Tester, &tester_process_output, true);
+
+    // The local tester is not limited in memory, so only the time limit is checked.
+    if tester_process_output.timed_out {
+        return Ok(EvaluationRecord::rejected(
             seed,
-            cmd_args: cmd_args_for_execute_submission.to_owned(),
-            output: tester_process_output
-        }
-    );
+            Status::TimeLimitExceeded,
+            format!("The {} exceeded the time limit.", Program::Tester),
+            execution_time,
+            &resource_usage,
+        ));
+    }
 
-    // Regular expression for retrieving a score.
-    let score_regex = Regex::new(r"\bScore *= *(?<score>[0-9]*)\b")
-        .with_context(|| "Failed to compile regular expression.")?;
+    let tester_process_output = tester_process_output.output;
+
+    if !tester_process_output.status.success() && score_extractor.requires_success() {
+        return Err(ExecuteCommandError::new(
+            config,
+            seed,
+            Program::Tester,
+            cmd_args_for_execute_tester,
+            &tester_process_output,
+            execution_time,
+            resource_usage,
+        )?
+        .into());
+    }
 
-    // Closure for getting scores from strings.
-    let retrieve_score = |text: &str| {
-        score_regex
-            .captures(text)
-            .and_then(|caps| caps["score"].parse::<i64>().ok())
+    // Retrieve the score from the output of the local tester.
+    let Some(score) = score_extractor.extract(config, seed, &tester_process_output)? else {
+        return Err(ScoreNotFoundError::new(
+            config,
+            seed,
+            &cmd_args_for_execute_tester,
+            &tester_process_output,
+            true,
+        )?
+        .into());
     };
 
-    let stdout = String::from_utf8(tester_process_output.stdout.clone())?;
-    let stderr = String::from_utf8(tester_process_output.stderr.clone())?;
+    // Retrieves the metrics and the progress from the standard error outputs of the submission code and the local tester.
+    let stderrs: [&[u8]; 2] = [
+        &submission_process_output.stderr,
+        &tester_process_output.stderr,
+    ];
+    let metrics = metric_extractor.extract(&stderrs, seed);
+    let progress = progress_extractor.extract(&stderrs, seed);
 
-    // Retrieve the score from the output of the local tester.
-    let Some(score) = retrieve_score(&stdout).or(retrieve_score(&stderr)) else {
-        bail!(format!(
-            "
-Failed to retrieve score.
+    Ok(EvaluationRecord {
+        metrics,
+        progress,
+        ..EvaluationRecord::accepted(seed, score, execution_time, &resource_usage)
+    })
+}
 
-Seed: {}
+/// Saves the output stream of a process to the file, creating its directory if necessary.
+///
+/// Nothing is saved if the path is not specified.
+fn save_stream(file_path: Option<PathBuf>, contents: &[u8]) -> anyhow::Result<()> {
+    let Some(file_path) = file_path else {
+        return Ok(());
+    };
 
-------------------------------- Standard Output --------------------------------
-{:?}
---------------------------------------------------------------------------------
+    if let Some(dir) = file_path.parent() {
+        create_dir_all(dir)
+            .with_context(|| format!("Failed to create the directory of `{:?}`.", file_path))?;
+    }
 
----------------------------- Standard Error Output -----------------------------
-{:?}
---------------------------------------------------------------------------------
-",
-            seed, tester_process_output.stdout, tester_process_output.stderr,
+    write(&file_path, contents).with_context(|| format!("Failed to write to `{:?}`.", file_path))
+}
+
+/// Returns the verdict and its description if the process exceeded the time limit or the memory limit.
+fn check_limits(
+    config: &Config,
+    program: Program,
+    process_output: &ProcessOutput,
+) -> Option<(Status, String)> {
+    if process_output.timed_out {
+        return Some((
+            Status::TimeLimitExceeded,
+            format!("The {} exceeded the time limit.", program),
+        ));
+    }
+
+    if exceeded_memory_limit(process_output, config.memory_limit(), config.rss_limit()) {
+        return Some((
+            Status::MemoryLimitExceeded,
+            format!("The {} exceeded the memory limit.", program),
         ));
+    }
+
+    None
+}
+
+/// Program executed during the evaluation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Program {
+    /// The submission code.
+    Submission,
+
+    /// The local tester.
+    Tester,
+}
+
+impl std::fmt::Display for Program {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Program::Submission => write!(f, "submission code"),
+            Program::Tester => write!(f, "local tester"),
+        }
+    }
+}
+
+/// Saves the full output of the failed command to the failure log file of the seed.
+///
+/// Returns the path of the file, or `None` if the path is not specified.
+fn save_failure_log(
+    config: &Config,
+    seed: usize,
+    cmd_args: &[String],
+    output: &Output,
+) -> anyhow::Result<Option<PathBuf>> {
+    let Some(file_path) = config.failure_log_file_path(seed) else {
+        return Ok(None);
     };
 
-    Ok(EvaluationRecord {
-        seed,
-        score,
-        execution_time,
-    })
+    // Writes the output as it is, since it may not be valid UTF-8.
+    let mut contents = format!(
+        "Command line arguments: {:?}\nExit code: {}\n\n{}\n",
+        cmd_args, output.status, STDOUT_HEADER
+    )
+    .into_bytes();
+    contents.extend_from_slice(&output.stdout);
+    contents.extend_from_slice(format!("\n{}\n", STDERR_HEADER).as_bytes());
+    contents.extend_from_slice(&output.stderr);
+
+    save_stream(Some(file_path.clone()), &contents)?;
+
+    Ok(Some(file_path))
 }
 
-/// Spawns a child process that executes the specified command.
-fn spawn_process(cmd_args: &[String]) -> anyhow::Result<std::process::Child> {
-    let program = cmd_args
-        .first()
-        .with_context(|| "The execution command is empty.")?;
-
-    Command::new(program)
-        .args(&cmd_args[1..])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .with_context(|| {
-            format!(
-                "
-Failed to start the child process.
-List of arguments: {:?}
-",
-                cmd_args
-            )
-        })
+/// Header of the standard output in error messages.
+const STDOUT_HEADER: &str =
+    "------------------------------- Standard Output --------------------------------";
+
+/// Header of the standard error output in error messages.
+const STDERR_HEADER: &str =
+    "---------------------------- Standard Error Output -----------------------------";
+
+/// Writes the note about the failure log file if it is saved.
+fn write_failure_log_note(
+    f: &mut std::fmt::Formatter<'_>,
+    failure_log_file: &Option<PathBuf>,
+) -> std::fmt::Result {
+    match failure_log_file {
+        Some(file_path) => write!(f, "\nThe full output is saved in `{:?}`.\n", file_path),
+        None => Ok(()),
+    }
 }
 
 #[derive(Debug)]
 pub struct ExecuteCommandError {
     pub seed: usize,
+    pub program: Program,
     pub cmd_args: Vec<String>,
-    pub output: std::process::Output,
+    pub status: ExitStatus,
+
+    /// Excerpt of the standard output.
+    pub stdout: String,
+
+    /// Excerpt of the standard error output.
+    pub stderr: String,
+
+    /// Path of the file in which the full output is saved.
+    pub failure_log_file: Option<PathBuf>,
+
+    /// Execution time in seconds of the submission code.
+    pub execution_time: f64,
+
+    /// Resources used by the submission code.
+    pub resource_usage: ResourceUsage,
+}
+
+impl ExecuteCommandError {
+    /// Creates the error of the failed command, saving its full output to the failure log file.
+    ///
+    /// `execution_time` and `resource_usage` are those measured for the submission code,
+    /// which are kept in the record of the seed.
+    fn new(
+        config: &Config,
+        seed: usize,
+        program: Program,
+        cmd_args: Vec<String>,
+        output: &Output,
+        execution_time: f64,
+        resource_usage: ResourceUsage,
+    ) -> anyhow::Result<Self> {
+        let failure_log_file = save_failure_log(config, seed, &cmd_args, output)?;
+
+        Ok(Self {
+            seed,
+            program,
+            cmd_args,
+            status: output.status,
+            stdout: config.output_excerpt(&output.stdout),
+            stderr: config.output_excerpt(&output.stderr),
+            failure_log_file,
+            execution_time,
+            resource_usage,
+        })
+    }
 }
 
 impl std::fmt::Display for ExecuteCommandError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let stdout = String::from_utf8(self.output.stdout.clone()).unwrap();
-        let stderr = String::from_utf8(self.output.stderr.clone()).unwrap();
-
         write!(
             f,
             "
-Failed to execute command.
+Failed to execute the {}.
 
 Exit code: {}
 
@@ -294,17 +613,102 @@ Seed: {}
 
 Command line arguments: {:?},
 
-------------------------------- Standard Output --------------------------------
+{}
 {}
 --------------------------------------------------------------------------------
 
----------------------------- Standard Error Output -----------------------------
+{}
 {}
 --------------------------------------------------------------------------------
 ",
-            self.output.status, self.seed, self.cmd_args, stdout, stderr,
-        )
+            self.program,
+            self.status,
+            self.seed,
+            self.cmd_args,
+            STDOUT_HEADER,
+            self.stdout,
+            STDERR_HEADER,
+            self.stderr,
+        )?;
+
+        write_failure_log_note(f, &self.failure_log_file)
     }
 }
 
 impl std::error::Error for ExecuteCommandError {}
+
+#[derive(Debug)]
+pub struct ScoreNotFoundError {
+    pub seed: usize,
+
+    /// Excerpt of the standard output, or `None` if it is the output of the submission code.
+    pub stdout: Option<String>,
+
+    /// Excerpt of the standard error output.
+    pub stderr: String,
+
+    /// Path of the file in which the full output is saved.
+    pub failure_log_file: Option<PathBuf>,
+}
+
+impl ScoreNotFoundError {
+    /// Creates the error of the local tester whose output has no score, saving its full output to the failure log file.
+    ///
+    /// The standard output is shown only if `show_stdout` is `true`.
+    fn new(
+        config: &Config,
+        seed: usize,
+        cmd_args: &[String],
+        output: &Output,
+        show_stdout: bool,
+    ) -> anyhow::Result<Self> {
+        let failure_log_file = save_failure_log(config, seed, cmd_args, output)?;
+
+        Ok(Self {
+            seed,
+            stdout: show_stdout.then(|| config.output_excerpt(&output.stdout)),
+            stderr: config.output_excerpt(&output.stderr),
+            failure_log_file,
+        })
+    }
+}
+
+impl std::fmt::Display for ScoreNotFoundError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "
+Failed to retrieve score.
+
+Seed: {}
+",
+            self.seed
+        )?;
+
+        if let Some(stdout) = &self.stdout {
+            write!(
+                f,
+                "
+{}
+{}
+--------------------------------------------------------------------------------
+",
+                STDOUT_HEADER, stdout
+            )?;
+        }
+
+        write!(
+            f,
+            "
+{}
+{}
+--------------------------------------------------------------------------------
+",
+            STDERR_HEADER, self.stderr
+        )?;
+
+        write_failure_log_note(f, &self.failure_log_file)
+    }
+}
+
+impl std::error::Error for ScoreNotFoundError {}
@@ -0,0 +1,378 @@
+//! Provides functions to spawn child processes and to wait for them with a time limit.
+
+use std::{
+    io::Read,
+    os::unix::process::{CommandExt, ExitStatusExt},
+    process::{Child, ExitStatus, Output, Stdio},
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::{bail, Context};
+
+use crate::{config::CommandSpec, evaluation::stop_watch::Stopwatch};
+
+/// Output of a child process that has been waited for.
+#[derive(Debug)]
+pub struct ProcessOutput {
+    /// Exit status and the contents of the standard output and the standard error output.
+    pub output: Output,
+
+    /// Elapsed time in seconds from the start of the stopwatch until the process terminated.
+    pub execution_time: f64,
+
+    /// Whether the process was killed because it exceeded the time limit.
+    pub timed_out: bool,
+
+    /// Resources used by the process.
+    pub resource_usage: ResourceUsage,
+}
+
+/// Resources used by a child process, including its descendants that it has waited for.
+#[derive(Debug, Clone, Copy)]
+pub struct ResourceUsage {
+    /// CPU time in seconds spent in user mode.
+    pub user_time: f64,
+
+    /// CPU time in seconds spent in kernel mode.
+    pub system_time: f64,
+
+    /// Peak resident set size in KiB.
+    pub peak_memory: u64,
+}
+
+impl ResourceUsage {
+    /// Converts from `rusage` returned by the system.
+    fn from_rusage(rusage: &libc::rusage) -> Self {
+        let to_secs = |time: libc::timeval| time.tv_sec as f64 + time.tv_usec as f64 / 1e6;
+
+        Self {
+            user_time: to_secs(rusage.ru_utime),
+            system_time: to_secs(rusage.ru_stime),
+            peak_memory: rusage.ru_maxrss as u64,
+        }
+    }
+}
+
+/// Spawns a child process that executes the specified command.
+///
+/// The standard input of the child process is connected to `stdin`, such as an input file opened for reading,
+/// and the standard output and the standard error output are piped.
+///
+/// The child process becomes the leader of a new process group,
+/// so that all of its descendants can be killed together when the time limit is exceeded.
+///
+/// If `memory_limit` (in bytes) is specified, the address space of the child process is limited to it.
+/// The limit is inherited by the processes that the child process spawns.
+pub fn spawn_process(
+    command_spec: &CommandSpec,
+    stdin: Stdio,
+    memory_limit: Option<u64>,
+) -> anyhow::Result<Child> {
+    let mut command = command_spec.to_command()?;
+    command
+        .stdin(stdin)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0);
+
+    if let Some(memory_limit) = memory_limit {
+        let limit = libc::rlimit {
+            rlim_cur: memory_limit as libc::rlim_t,
+            rlim_max: memory_limit as libc::rlim_t,
+        };
+
+        // `setrlimit` is async-signal-safe, so it can be called between `fork` and `exec`.
+        unsafe {
+            command.pre_exec(move || {
+                if libc::setrlimit(libc::RLIMIT_AS, &limit) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+
+                Ok(())
+            });
+        }
+    }
+
+    command.spawn().with_context(|| {
+        format!(
+            "
+Failed to start the child process.
+List of arguments: {:?}
+",
+            command_spec.cmd_args
+        )
+    })
+}
+
+/// Waits for the child process to terminate while collecting its standard output and standard error output.
+///
+/// If `timeout` elapses (measured by `stopwatch`), `SIGTERM` is sent to the process group of the child.
+/// If the child is still alive after `kill_grace_period`, the process group is killed with `SIGKILL`.
+/// Any process remaining in the process group after the child has terminated is also killed with `SIGKILL`.
+pub fn wait_with_timeout(
+    mut child: Child,
+    stopwatch: Stopwatch,
+    timeout: Option<Duration>,
+    kill_grace_period: Duration,
+) -> anyhow::Result<ProcessOutput> {
+    // Drains the standard output and the standard error output concurrently.
+    let stdout_reader = child.stdout.take().map(spawn_reader);
+    let stderr_reader = child.stderr.take().map(spawn_reader);
+
+    // Waits for the child process on a dedicated thread so that the wait can be timed out.
+    // The flag is set under the lock when the child is reaped, after which the process group ID may be reused.
+    let pid = child.id();
+    let reaped = Arc::new(Mutex::new(false));
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn({
+        let reaped = Arc::clone(&reaped);
+        move || {
+            let waited = wait_and_kill_group(pid, &reaped)
+                .map(|(status, resource_usage)| (status, resource_usage, stopwatch.elapsed_time()));
+            let _ = sender.send(waited);
+        }
+    });
+
+    // Sends the signal to the process group only while the child has not been reaped.
+    let signal_group = |signal| {
+        let reaped = reaped.lock().unwrap_or_else(|err| err.into_inner());
+        if !*reaped {
+            kill_process_group(pid, signal);
+        }
+    };
+
+    let mut timed_out = false;
+    let waited = match timeout {
+        Some(timeout) => {
+            let remaining_time =
+                timeout.saturating_sub(Duration::from_secs_f64(stopwatch.elapsed_time()));
+
+            match receiver.recv_timeout(remaining_time) {
+                Err(RecvTimeoutError::Timeout) => {
+                    timed_out = true;
+                    signal_group(libc::SIGTERM);
+
+                    match receiver.recv_timeout(kill_grace_period) {
+                        Err(RecvTimeoutError::Timeout) => {
+                            signal_group(libc::SIGKILL);
+                            receiver.recv().ok()
+                        }
+                        waited => waited.ok(),
+                    }
+                }
+                waited => waited.ok(),
+            }
+        }
+        None => receiver.recv().ok(),
+    };
+
+    let Some(waited) = waited else {
+        bail!("The thread waiting for the child process terminated unexpectedly.");
+    };
+    let (status, resource_usage, execution_time) =
+        waited.with_context(|| "Failed to wait for the child process.")?;
+
+    let stdout = join_reader(stdout_reader)?;
+    let stderr = join_reader(stderr_reader)?;
+
+    Ok(ProcessOutput {
+        output: Output {
+            status,
+            stdout,
+            stderr,
+        },
+        execution_time,
+        timed_out,
+        resource_usage,
+    })
+}
+
+/// Returns `true` if the process exceeded the memory limit.
+///
+/// The address space limit `memory_limit` makes memory allocation fail, but a failed process does not tell
+/// whether it was caused by the limit, so it is regarded as having exceeded the limit only if its peak resident set size
+/// reached `MEMORY_LIMIT_RATIO` of the limit.
+/// Independently, the peak resident set size is compared with `rss_limit`.
+pub fn exceeded_memory_limit(
+    process_output: &ProcessOutput,
+    memory_limit: Option<u64>,
+    rss_limit: Option<u64>,
+) -> bool {
+    let peak_memory = process_output.resource_usage.peak_memory * 1024;
+
+    let failed = !process_output.output.status.success();
+    if failed
+        && memory_limit.is_some_and(|memory_limit| {
+            peak_memory as f64 >= memory_limit as f64 * MEMORY_LIMIT_RATIO
+        })
+    {
+        return true;
+    }
+
+    rss_limit.is_some_and(|rss_limit| peak_memory > rss_limit)
+}
+
+/// Ratio of the peak resident set size to the memory limit above which a failed process is regarded as MLE.
+const MEMORY_LIMIT_RATIO: f64 = 0.9;
+
+/// Waits for the child process to terminate, kills the rest of its process group and reaps it.
+///
+/// The child process is left as a zombie until the process group is killed,
+/// so that the process group ID is not reused by an unrelated process in the meantime.
+/// The descendants left behind are killed because they may keep the output pipes open and block the reader threads forever.
+/// `reaped` is set while it is locked so that the process group is not signaled after the child has been reaped.
+fn wait_and_kill_group(
+    pid: u32,
+    reaped: &Mutex<bool>,
+) -> std::io::Result<(ExitStatus, ResourceUsage)> {
+    retry_on_interrupt(|| {
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        unsafe {
+            libc::waitid(
+                libc::P_PID,
+                pid as libc::id_t,
+                &mut info,
+                libc::WEXITED | libc::WNOWAIT,
+            )
+        }
+    })?;
+
+    let mut reaped = reaped.lock().unwrap_or_else(|err| err.into_inner());
+    kill_process_group(pid, libc::SIGKILL);
+    let waited = wait_for_resource_usage(pid);
+    *reaped = true;
+
+    waited
+}
+
+/// Waits for the child process to terminate and returns its exit status and resource usage.
+///
+/// The child process is reaped by `wait4`, so `Child::wait` must not be called on it afterwards.
+fn wait_for_resource_usage(pid: u32) -> std::io::Result<(ExitStatus, ResourceUsage)> {
+    let mut status = 0;
+    let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+
+    retry_on_interrupt(|| unsafe { libc::wait4(pid as libc::pid_t, &mut status, 0, &mut rusage) })?;
+
+    Ok((
+        ExitStatus::from_raw(status),
+        ResourceUsage::from_rusage(&rusage),
+    ))
+}
+
+/// Calls the system call until it is not interrupted by a signal.
+fn retry_on_interrupt<F>(mut syscall: F) -> std::io::Result<()>
+where
+    F: FnMut() -> libc::c_int,
+{
+    loop {
+        if syscall() != -1 {
+            return Ok(());
+        }
+
+        let err = std::io::Error::last_os_error();
+        if err.kind() != std::io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+}
+
+/// Spawns a thread that reads everything from the pipe.
+fn spawn_reader<R>(mut pipe: R) -> thread::JoinHandle<std::io::Result<Vec<u8>>>
+where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut buf = vec![];
+        pipe.read_to_end(&mut buf)?;
+        Ok(buf)
+    })
+}
+
+/// Waits for the reader thread and returns the bytes it has read.
+fn join_reader(
+    reader: Option<thread::JoinHandle<std::io::Result<Vec<u8>>>>,
+) -> anyhow::Result<Vec<u8>> {
+    let Some(reader) = reader else {
+        return Ok(vec![]);
+    };
+
+    match reader.join() {
+        Ok(buf) => buf.with_context(|| "Failed to read the output of the child process."),
+        Err(_) => bail!("The thread reading the output of the child process panicked."),
+    }
+}
+
+/// Sends the signal to all processes in the process group led by `pid`.
+fn kill_process_group(pid: u32, signal: libc::c_int) {
+    // Errors are ignored because the process group may already have terminated.
+    unsafe {
+        libc::killpg(pid as libc::pid_t, signal);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs the shell script and waits for it with the time limit.
+    fn run(script: &str, timeout: Option<f64>, kill_grace_period: f64) -> ProcessOutput {
+        let command_spec = CommandSpec {
+            cmd_args: vec!["sh".to_owned(), "-c".to_owned(), script.to_owned()],
+            envs: vec![],
+            cwd: None,
+        };
+        let child = spawn_process(&command_spec, Stdio::null(), None).unwrap();
+
+        wait_with_timeout(
+            child,
+            Stopwatch::start(),
+            timeout.map(Duration::from_secs_f64),
+            Duration::from_secs_f64(kill_grace_period),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_wait_with_timeout_exits_normally() {
+        let process_output = run("echo done", Some(10.0), 1.0);
+
+        assert!(!process_output.timed_out);
+        assert!(process_output.output.status.success());
+        assert_eq!(process_output.output.stdout, b"done\n");
+    }
+
+    #[test]
+    fn test_wait_with_timeout_terminates() {
+        let process_output = run("exec sleep 10", Some(0.2), 5.0);
+
+        assert!(process_output.timed_out);
+        assert_eq!(process_output.output.status.signal(), Some(libc::SIGTERM));
+        assert!(process_output.execution_time < 5.0);
+    }
+
+    #[test]
+    fn test_wait_with_timeout_kills_after_grace_period() {
+        let process_output = run("trap '' TERM; sleep 10", Some(0.2), 0.2);
+
+        assert!(process_output.timed_out);
+        assert_eq!(process_output.output.status.signal(), Some(libc::SIGKILL));
+        assert!(process_output.execution_time < 5.0);
+    }
+
+    #[test]
+    fn test_wait_with_timeout_kills_orphans() {
+        let stopwatch = Stopwatch::start();
+        let process_output = run("sleep 10 & echo done", None, 1.0);
+
+        assert!(!process_output.timed_out);
+        assert!(process_output.output.status.success());
+        assert_eq!(process_output.output.stdout, b"done\n");
+        assert!(stopwatch.elapsed_time() < 5.0);
+    }
+}
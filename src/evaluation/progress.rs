@@ -0,0 +1,118 @@
+//! Provides a structure to collect the progress of the score over time from the standard error output.
+
+use anyhow::{ensure, Context};
+use regex::Regex;
+
+use crate::config::ProgressConfig;
+
+/// Collects the progress lines according to the progress configuration.
+#[derive(Debug, Clone)]
+pub struct ProgressExtractor {
+    /// Regular expression for retrieving a progress line, or `None` if the progress is not collected.
+    regex: Option<Regex>,
+}
+
+impl ProgressExtractor {
+    /// Compiles the regular expression in the progress configuration.
+    pub fn new(progress_config: Option<&ProgressConfig>) -> anyhow::Result<Self> {
+        let Some(progress_config) = progress_config else {
+            return Ok(Self { regex: None });
+        };
+
+        let pattern = &progress_config.pattern;
+        let regex = Regex::new(pattern)
+            .with_context(|| format!("Failed to compile regular expression `{}`.", pattern))?;
+
+        for group in ["time", "score"] {
+            ensure!(
+                regex.capture_names().flatten().any(|name| name == group),
+                "The regular expression `{}` does not have the named group `{}`.",
+                pattern,
+                group
+            );
+        }
+
+        Ok(Self { regex: Some(regex) })
+    }
+
+    /// Collects the pairs of the elapsed time and the score from the outputs, sorted by time.
+    ///
+    /// A progress line whose time or score cannot be parsed as a finite number is skipped with a warning.
+    pub fn extract(&self, outputs: &[&[u8]], seed: usize) -> Vec<(f64, f64)> {
+        let Some(regex) = &self.regex else {
+            return vec![];
+        };
+
+        let mut progress = vec![];
+
+        for output in outputs {
+            // Decodes the output lossily since it may not be valid UTF-8.
+            let text = String::from_utf8_lossy(output);
+
+            for caps in regex.captures_iter(&text) {
+                let (Some(time), Some(score)) = (caps.name("time"), caps.name("score")) else {
+                    continue;
+                };
+                let (time, score) = (time.as_str().trim(), score.as_str().trim());
+
+                match (time.parse::<f64>(), score.parse::<f64>()) {
+                    (Ok(time), Ok(score)) if time.is_finite() && score.is_finite() => {
+                        progress.push((time, score));
+                    }
+                    _ => eprintln!(
+                        "Warning: Failed to parse `{}` as the progress for seed {}.",
+                        &caps[0], seed
+                    ),
+                }
+            }
+        }
+
+        progress.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+        progress
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_progress_with_default_pattern() {
+        let progress_config: ProgressConfig = toml::from_str("").unwrap();
+        let progress_extractor = ProgressExtractor::new(Some(&progress_config)).unwrap();
+
+        let stderr =
+            b"progress t=1.5 score=200\nprogress t=0.5 score=1e2\nprogress t=2 score=-3.5E-1\n";
+        assert_eq!(
+            progress_extractor.extract(&[stderr], 0),
+            [(0.5, 100.0), (1.5, 200.0), (2.0, -0.35)]
+        );
+
+        // Text that is not a number does not match the default pattern.
+        let stderr = b"progress t=1..5 score=200\nprogress t=- score=1\nprogress t=1 score=2\n";
+        assert_eq!(progress_extractor.extract(&[stderr], 0), [(1.0, 2.0)]);
+    }
+
+    #[test]
+    fn skip_unparsable_progress() {
+        let progress_config: ProgressConfig =
+            toml::from_str(r#"pattern = 't=(?<time>\S+) s=(?<score>\S+)'"#).unwrap();
+        let progress_extractor = ProgressExtractor::new(Some(&progress_config)).unwrap();
+
+        let outputs: [&[u8]; 2] = [b"t=1 s=10\nt=x s=20\n", b"t=inf s=30\nt=0 s=5\n"];
+        assert_eq!(
+            progress_extractor.extract(&outputs, 0),
+            [(0.0, 5.0), (1.0, 10.0)]
+        );
+    }
+
+    #[test]
+    fn collect_nothing_without_configuration() {
+        let progress_extractor = ProgressExtractor::new(None).unwrap();
+
+        assert!(progress_extractor
+            .extract(&[b"progress t=1 score=2"], 0)
+            .is_empty());
+    }
+}
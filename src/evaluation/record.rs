@@ -1,48 +1,335 @@
-use std::path::Path;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
 
 use anyhow::{ensure, Context};
 use itertools::Itertools;
+use serde::Serialize;
 
-#[derive(Debug, Clone, Copy, serde::Serialize)]
+use crate::{
+    config::{Objective, StatisticsConfig},
+    evaluation::{
+        best_score::{is_better, relative_score},
+        process::ResourceUsage,
+        ExecuteCommandError, Program, ScoreNotFoundError,
+    },
+    statistics,
+};
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct EvaluationRecord {
     pub seed: usize,
-    pub score: i64,
-    pub execution_time: f64,
+    #[serde(serialize_with = "serialize_score")]
+    pub score: Option<f64>,
+
+    /// Score relative to the best known score for the seed.
+    pub relative_score: Option<f64>,
+    pub execution_time: Option<f64>,
+
+    /// CPU time in seconds spent in user mode.
+    pub user_time: Option<f64>,
+
+    /// CPU time in seconds spent in kernel mode.
+    pub system_time: Option<f64>,
+
+    /// Peak resident set size in KiB.
+    pub peak_memory: Option<u64>,
+
+    /// Records written before the status was introduced are all accepted.
+    #[serde(default = "default_status")]
+    pub status: Status,
+
+    /// Short description of the failure.
+    #[serde(default)]
+    pub failure: Option<String>,
+
+    /// Tags attached to the seed in the seed file, written as a space-separated list.
+    #[serde(
+        default,
+        serialize_with = "serialize_tags",
+        deserialize_with = "deserialize_tags"
+    )]
+    pub tags: Vec<String>,
+
+    /// Features extracted from the input file.
+    /// In the CSV file, each feature is written in its own column `feature:NAME`.
+    #[serde(default)]
+    pub features: BTreeMap<String, f64>,
+
+    /// Metrics extracted from the standard error output.
+    /// In the CSV file, each metric is written in its own column `metric:NAME`.
+    #[serde(default)]
+    pub metrics: BTreeMap<String, f64>,
+
+    /// Pairs of the elapsed time and the score reported by the progress lines,
+    /// written as a space-separated list of `time:score`.
+    #[serde(
+        default,
+        serialize_with = "serialize_progress",
+        deserialize_with = "deserialize_progress"
+    )]
+    pub progress: Vec<(f64, f64)>,
+}
+
+impl EvaluationRecord {
+    /// Creates a record of the seed whose score was retrieved.
+    pub fn accepted(
+        seed: usize,
+        score: f64,
+        execution_time: f64,
+        resource_usage: &ResourceUsage,
+    ) -> Self {
+        Self {
+            seed,
+            score: Some(score),
+            relative_score: None,
+            execution_time: Some(execution_time),
+            user_time: Some(resource_usage.user_time),
+            system_time: Some(resource_usage.system_time),
+            peak_memory: Some(resource_usage.peak_memory),
+            status: Status::Accepted,
+            failure: None,
+            tags: vec![],
+            features: BTreeMap::new(),
+            metrics: BTreeMap::new(),
+            progress: vec![],
+        }
+    }
+
+    /// Creates a record of the seed whose process terminated without a score.
+    pub fn rejected(
+        seed: usize,
+        status: Status,
+        failure: String,
+        execution_time: f64,
+        resource_usage: &ResourceUsage,
+    ) -> Self {
+        Self {
+            score: None,
+            status,
+            failure: Some(failure),
+            ..Self::accepted(seed, 0.0, execution_time, resource_usage)
+        }
+    }
+
+    /// Creates a record of the seed that failed with the error.
+    ///
+    /// The execution time and the resource usage are kept if they were measured before the failure.
+    pub fn from_error(seed: usize, err: &anyhow::Error) -> Self {
+        let mut measured = None;
+
+        let (status, failure) = if let Some(err) = err.downcast_ref::<ExecuteCommandError>() {
+            measured = Some((err.execution_time, err.resource_usage));
+
+            let status = match err.program {
+                Program::Submission => Status::RuntimeError,
+                Program::Tester => Status::TesterError,
+            };
+            let failure = format!("The {} exited with {}.", err.program, err.status);
+
+            (status, failure)
+        } else if err.downcast_ref::<ScoreNotFoundError>().is_some() {
+            (
+                Status::ScoreNotFound,
+                "The score was not found in the output of the local tester.".to_owned(),
+            )
+        } else {
+            let message = err.to_string();
+            let failure = message
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .unwrap_or_default()
+                .to_owned();
+
+            (Status::InternalError, failure)
+        };
+
+        Self {
+            seed,
+            score: None,
+            relative_score: None,
+            execution_time: measured.map(|(execution_time, _)| execution_time),
+            user_time: measured.map(|(_, resource_usage)| resource_usage.user_time),
+            system_time: measured.map(|(_, resource_usage)| resource_usage.system_time),
+            peak_memory: measured.map(|(_, resource_usage)| resource_usage.peak_memory),
+            status,
+            failure: Some(failure),
+            tags: vec![],
+            features: BTreeMap::new(),
+            metrics: BTreeMap::new(),
+            progress: vec![],
+        }
+    }
+
+    /// Returns `true` if the score was retrieved successfully.
+    pub fn is_accepted(&self) -> bool {
+        self.status == Status::Accepted
+    }
+}
+
+/// Default value of `status`.
+fn default_status() -> Status {
+    Status::Accepted
+}
+
+/// Result of the evaluation for a seed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Status {
+    /// The score was retrieved successfully.
+    #[serde(rename = "AC")]
+    Accepted,
+
+    /// The process was killed because it exceeded the time limit.
+    #[serde(rename = "TLE")]
+    TimeLimitExceeded,
+
+    /// The process was terminated because it exceeded the memory limit.
+    #[serde(rename = "MLE")]
+    MemoryLimitExceeded,
+
+    /// The submission code exited with a non-zero status.
+    #[serde(rename = "RE")]
+    RuntimeError,
+
+    /// The local tester exited with a non-zero status.
+    #[serde(rename = "TE")]
+    TesterError,
+
+    /// The score was not found in the output of the local tester.
+    #[serde(rename = "SNF")]
+    ScoreNotFound,
+
+    /// The evaluation failed for other reasons, such as a missing input file.
+    #[serde(rename = "IE")]
+    InternalError,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let abbreviation = match self {
+            Status::Accepted => "AC",
+            Status::TimeLimitExceeded => "TLE",
+            Status::MemoryLimitExceeded => "MLE",
+            Status::RuntimeError => "RE",
+            Status::TesterError => "TE",
+            Status::ScoreNotFound => "SNF",
+            Status::InternalError => "IE",
+        };
+
+        write!(f, "{}", abbreviation)
+    }
 }
 
 /// Shows statistics about scores and execution times.
-pub fn show_statistics(evaluation_table: &[EvaluationRecord]) -> anyhow::Result<()> {
-    show_score_statistics(evaluation_table)?;
+///
+/// Only the seeds whose score was retrieved successfully are aggregated.
+pub fn show_statistics(
+    statistics_config: &StatisticsConfig,
+    evaluation_table: &[EvaluationRecord],
+) -> anyhow::Result<()> {
+    let accepted_table = evaluation_table
+        .iter()
+        .filter(|record| record.is_accepted())
+        .cloned()
+        .collect_vec();
+
+    if accepted_table.is_empty() {
+        println!("No seed was evaluated successfully.");
+        return Ok(());
+    }
+
+    show_score_statistics(statistics_config, &accepted_table)?;
     println!();
-    show_execution_time_statistics(evaluation_table)?;
+
+    if accepted_table
+        .iter()
+        .any(|record| record.relative_score.is_some())
+    {
+        show_relative_score_statistics(statistics_config, &accepted_table)?;
+        println!();
+    }
+
+    show_execution_time_statistics(statistics_config, &accepted_table)?;
+
+    if accepted_table
+        .iter()
+        .any(|record| !record.metrics.is_empty())
+    {
+        println!();
+        show_metric_statistics(statistics_config, &accepted_table);
+    }
+
+    if evaluation_table
+        .iter()
+        .any(|record| !record.tags.is_empty())
+    {
+        println!();
+        show_tag_statistics(evaluation_table);
+    }
+
+    if evaluation_table
+        .iter()
+        .any(|record| !record.features.is_empty())
+    {
+        println!();
+        show_feature_statistics(statistics_config, evaluation_table);
+    }
 
     Ok(())
 }
 
+/// Shows the list of seeds that failed to be evaluated.
+pub fn show_failures(evaluation_table: &[EvaluationRecord]) {
+    let failed_records = evaluation_table
+        .iter()
+        .filter(|record| !record.is_accepted())
+        .collect_vec();
+
+    println!(
+        "[Failures]\nCount: {} / {}",
+        failed_records.len(),
+        evaluation_table.len()
+    );
+    println!("{:>8}  {:<6}  Detail", "Seed", "Status");
+    for record in failed_records {
+        println!(
+            "{:>8}  {:<6}  {}",
+            record.seed,
+            record.status.to_string(),
+            record.failure.as_deref().unwrap_or_default()
+        );
+    }
+}
+
 /// Shows score statistics.
-fn show_score_statistics(evaluation_table: &[EvaluationRecord]) -> anyhow::Result<()> {
+fn show_score_statistics(
+    statistics_config: &StatisticsConfig,
+    evaluation_table: &[EvaluationRecord],
+) -> anyhow::Result<()> {
     ensure!(
         !evaluation_table.is_empty(),
-        "The evaluation table is empty."
+        "There are no seeds whose score was retrieved."
     );
 
     let total_score = evaluation_table
         .iter()
-        .map(|record| record.score)
-        .sum::<i64>();
-    let avg_score = total_score as f64 / evaluation_table.len() as f64;
+        .filter_map(|record| record.score)
+        .sum::<f64>();
+    let avg_score = total_score / evaluation_table.len() as f64;
 
     let (min_pos, max_pos) = match evaluation_table
         .iter()
-        .position_minmax_by_key(|record| record.score)
+        .position_minmax_by(|x, y| compare_optional(x.score, y.score))
     {
         itertools::MinMaxResult::NoElements => unreachable!(),
         itertools::MinMaxResult::OneElement(pos) => (pos, pos),
         itertools::MinMaxResult::MinMax(min_pos, max_pos) => (min_pos, max_pos),
     };
 
-    let min_record = evaluation_table[min_pos];
-    let max_record = evaluation_table[max_pos];
+    let min_record = &evaluation_table[min_pos];
+    let max_record = &evaluation_table[max_pos];
 
     print!(
         "\
@@ -54,39 +341,100 @@ Max: {} (seed = {})
 ",
         total_score,
         avg_score,
-        min_record.score,
+        min_record.score.unwrap(),
         min_record.seed,
-        max_record.score,
+        max_record.score.unwrap(),
         max_record.seed,
     );
 
+    let scores = evaluation_table
+        .iter()
+        .filter_map(|record| record.score)
+        .collect_vec();
+    show_distribution(statistics_config, &scores, "");
+
     Ok(())
 }
 
-/// Shows execution time statistics.
-fn show_execution_time_statistics(evaluation_table: &[EvaluationRecord]) -> anyhow::Result<()> {
+/// Shows statistics about scores relative to the best known scores.
+fn show_relative_score_statistics(
+    statistics_config: &StatisticsConfig,
+    evaluation_table: &[EvaluationRecord],
+) -> anyhow::Result<()> {
     ensure!(
         !evaluation_table.is_empty(),
-        "The evaluation table is empty."
+        "There are no seeds whose score was retrieved."
+    );
+
+    let relative_scores = evaluation_table
+        .iter()
+        .map(|record| record.relative_score.unwrap_or_default())
+        .collect_vec();
+
+    let total_relative_score = relative_scores.iter().sum::<f64>();
+    let avg_relative_score = total_relative_score / relative_scores.len() as f64;
+
+    let min_pos = relative_scores
+        .iter()
+        .position_min_by(|x, y| x.total_cmp(y))
+        .unwrap();
+    let best_num = relative_scores
+        .iter()
+        .filter(|&&relative_score| relative_score >= 1.0)
+        .count();
+
+    print!(
+        "\
+[Relative Score]
+Total: {:.3}
+Average: {:.3}%
+Min: {:.3}% (seed = {})
+Best: {} / {}
+",
+        total_relative_score,
+        100.0 * avg_relative_score,
+        100.0 * relative_scores[min_pos],
+        evaluation_table[min_pos].seed,
+        best_num,
+        relative_scores.len(),
+    );
+
+    let relative_score_percentages = relative_scores
+        .iter()
+        .map(|relative_score| 100.0 * relative_score)
+        .collect_vec();
+    show_distribution(statistics_config, &relative_score_percentages, "%");
+
+    Ok(())
+}
+
+/// Shows statistics about execution times, CPU times and peak memory usage.
+fn show_execution_time_statistics(
+    statistics_config: &StatisticsConfig,
+    evaluation_table: &[EvaluationRecord],
+) -> anyhow::Result<()> {
+    ensure!(
+        !evaluation_table.is_empty(),
+        "There are no seeds whose score was retrieved."
     );
 
     let total_exe_time = evaluation_table
         .iter()
-        .map(|record| record.execution_time)
+        .filter_map(|record| record.execution_time)
         .sum::<f64>();
     let avg_exe_time = total_exe_time / evaluation_table.len() as f64;
 
     let (min_pos, max_pos) = match evaluation_table
         .iter()
-        .position_minmax_by(|x, y| x.execution_time.partial_cmp(&y.execution_time).unwrap())
+        .position_minmax_by(|x, y| compare_optional(x.execution_time, y.execution_time))
     {
         itertools::MinMaxResult::NoElements => unreachable!(),
         itertools::MinMaxResult::OneElement(pos) => (pos, pos),
         itertools::MinMaxResult::MinMax(min_pos, max_pos) => (min_pos, max_pos),
     };
 
-    let min_record = evaluation_table[min_pos];
-    let max_record = evaluation_table[max_pos];
+    let min_record = &evaluation_table[min_pos];
+    let max_record = &evaluation_table[max_pos];
 
     print!(
         "\
@@ -98,16 +446,662 @@ Max: {:.3} (seed = {})
 ",
         total_exe_time,
         avg_exe_time,
-        min_record.execution_time,
+        min_record.execution_time.unwrap(),
         min_record.seed,
-        max_record.execution_time,
+        max_record.execution_time.unwrap(),
         max_record.seed,
     );
 
+    let exe_times = evaluation_table
+        .iter()
+        .filter_map(|record| record.execution_time)
+        .collect_vec();
+    show_distribution(statistics_config, &exe_times, "");
+
+    if let Some(time_limit) = statistics_config.time_limit {
+        let over_seeds = evaluation_table
+            .iter()
+            .filter(|record| record.execution_time.is_some_and(|time| time > time_limit))
+            .map(|record| record.seed)
+            .collect_vec();
+
+        print!(
+            "Over Time Limit ({:.3}): {} / {}",
+            time_limit,
+            over_seeds.len(),
+            evaluation_table.len()
+        );
+        if !over_seeds.is_empty() {
+            print!(" (seeds = {})", over_seeds.iter().join(", "));
+        }
+        println!();
+    }
+
+    let record_num = evaluation_table.len() as f64;
+    let avg_user_time = evaluation_table
+        .iter()
+        .filter_map(|record| record.user_time)
+        .sum::<f64>()
+        / record_num;
+    let avg_system_time = evaluation_table
+        .iter()
+        .filter_map(|record| record.system_time)
+        .sum::<f64>()
+        / record_num;
+    let avg_peak_memory = evaluation_table
+        .iter()
+        .filter_map(|record| record.peak_memory)
+        .sum::<u64>() as f64
+        / record_num;
+
+    let cpu_time = |record: &EvaluationRecord| {
+        record.user_time.unwrap_or_default() + record.system_time.unwrap_or_default()
+    };
+    let max_cpu_record = evaluation_table
+        .iter()
+        .max_by(|x, y| cpu_time(x).total_cmp(&cpu_time(y)))
+        .unwrap();
+    let max_memory_record = evaluation_table
+        .iter()
+        .max_by_key(|record| record.peak_memory)
+        .unwrap();
+
+    print!(
+        "\
+Average CPU Time: {:.3} (user = {:.3}, system = {:.3})
+Max CPU Time: {:.3} (seed = {})
+Average Peak Memory: {:.0} KiB
+Max Peak Memory: {} KiB (seed = {})
+",
+        avg_user_time + avg_system_time,
+        avg_user_time,
+        avg_system_time,
+        cpu_time(max_cpu_record),
+        max_cpu_record.seed,
+        avg_peak_memory,
+        max_memory_record.peak_memory.unwrap_or_default(),
+        max_memory_record.seed,
+    );
+
     Ok(())
 }
 
-/// Outputs score and execution time record per seed to CSV file.
+/// Shows statistics about each metric over the seeds in which it was found.
+fn show_metric_statistics(
+    statistics_config: &StatisticsConfig,
+    evaluation_table: &[EvaluationRecord],
+) {
+    let metric_names: BTreeSet<&str> = evaluation_table
+        .iter()
+        .flat_map(|record| record.metrics.keys().map(String::as_str))
+        .collect();
+
+    for (i, name) in metric_names.into_iter().enumerate() {
+        let values = evaluation_table
+            .iter()
+            .filter_map(|record| Some((record.metrics.get(name).copied()?, record.seed)))
+            .collect_vec();
+
+        let total = values.iter().map(|&(value, _)| value).sum::<f64>();
+        let (min_value, min_seed) = values
+            .iter()
+            .copied()
+            .min_by(|x, y| x.0.total_cmp(&y.0))
+            .unwrap();
+        let (max_value, max_seed) = values
+            .iter()
+            .copied()
+            .max_by(|x, y| x.0.total_cmp(&y.0))
+            .unwrap();
+
+        if i != 0 {
+            println!();
+        }
+        print!(
+            "\
+[Metric Statistics: {}]
+Found: {} / {}
+Total: {}
+Average: {:.3}
+Min: {} (seed = {})
+Max: {} (seed = {})
+",
+            name,
+            values.len(),
+            evaluation_table.len(),
+            total,
+            total / values.len() as f64,
+            min_value,
+            min_seed,
+            max_value,
+            max_seed,
+        );
+
+        let values = values.iter().map(|&(value, _)| value).collect_vec();
+        show_distribution(statistics_config, &values, "");
+    }
+}
+
+/// Shows the average progress of the normalized score over the elapsed time.
+///
+/// The score of each seed is normalized by the best score in its progress, so that it reaches 100% at the best.
+/// At each point in time, the latest progress of each seed is averaged over the seeds that have reported progress.
+pub fn show_progress_curve(
+    objective: Objective,
+    point_num: usize,
+    evaluation_table: &[EvaluationRecord],
+) {
+    let progress_table = evaluation_table
+        .iter()
+        .filter(|record| record.is_accepted() && !record.progress.is_empty())
+        .map(|record| {
+            let best_score = record
+                .progress
+                .iter()
+                .map(|&(_, score)| score)
+                .reduce(|best, score| {
+                    if is_better(score, best, objective) {
+                        score
+                    } else {
+                        best
+                    }
+                })
+                .unwrap();
+
+            record
+                .progress
+                .iter()
+                .map(|&(time, score)| (time, relative_score(score, best_score, objective)))
+                .collect_vec()
+        })
+        .collect_vec();
+
+    if progress_table.is_empty() {
+        return;
+    }
+
+    let max_time = progress_table
+        .iter()
+        .filter_map(|progress| progress.last())
+        .map(|&(time, _)| time)
+        .fold(0.0, f64::max);
+
+    println!("[Progress Curve]");
+    println!("{:>10}  {:>10}  {:>16}", "Time", "Seeds", "Avg Norm. Score");
+
+    for i in 1..=point_num {
+        let time = max_time * i as f64 / point_num as f64;
+
+        // Takes the latest progress of each seed at the time.
+        let normalized_scores = progress_table
+            .iter()
+            .filter_map(|progress| {
+                progress
+                    .iter()
+                    .take_while(|&&(other, _)| other <= time)
+                    .last()
+                    .map(|&(_, normalized_score)| normalized_score)
+            })
+            .collect_vec();
+
+        let average = if normalized_scores.is_empty() {
+            "-".to_owned()
+        } else {
+            format!("{:.3}%", 100.0 * statistics::mean(&normalized_scores))
+        };
+
+        println!(
+            "{:>10.3}  {:>10}  {:>16}",
+            time,
+            format!("{} / {}", normalized_scores.len(), progress_table.len()),
+            average
+        );
+    }
+}
+
+/// Shows scores and execution times grouped by the tags of the seeds.
+///
+/// A seed with several tags is counted in each of them, and seeds without tags are grouped as `(untagged)`.
+fn show_tag_statistics(evaluation_table: &[EvaluationRecord]) {
+    /// Name of the group of seeds without tags.
+    const UNTAGGED: &str = "(untagged)";
+
+    let mut tag_to_records: BTreeMap<&str, Vec<&EvaluationRecord>> = BTreeMap::new();
+    for record in evaluation_table {
+        if record.tags.is_empty() {
+            tag_to_records.entry(UNTAGGED).or_default().push(record);
+        }
+
+        for tag in &record.tags {
+            tag_to_records.entry(tag).or_default().push(record);
+        }
+    }
+
+    let groups = tag_to_records
+        .into_iter()
+        .map(|(tag, records)| (tag.to_owned(), records))
+        .collect_vec();
+
+    println!("[Tag Statistics]");
+    show_group_table("Tag", &groups);
+}
+
+/// Shows scores and execution times for each combination of the parameters evaluated by `sweep`, and the best combination.
+///
+/// The best combination is the one with the most accepted seeds, and ties are broken by the average relative score,
+/// or by the average score if relative scores are not available.
+pub fn show_sweep_statistics(objective: Objective, sweep_table: &[(String, &[EvaluationRecord])]) {
+    let groups = sweep_table
+        .iter()
+        .map(|(label, records)| (label.clone(), records.iter().collect_vec()))
+        .collect_vec();
+
+    println!("[Sweep Statistics]");
+    show_group_table("Parameters", &groups);
+
+    // Shows the number of failed seeds by status for each combination with failures.
+    let failures = sweep_table
+        .iter()
+        .filter_map(|(label, records)| {
+            let status_counts = records
+                .iter()
+                .filter(|record| !record.is_accepted())
+                .map(|record| record.status.to_string())
+                .counts();
+            if status_counts.is_empty() {
+                return None;
+            }
+
+            let failed_num = status_counts.values().sum::<usize>();
+            let detail = status_counts
+                .into_iter()
+                .sorted()
+                .map(|(status, count)| format!("{}: {}", status, count))
+                .join(", ");
+
+            Some((label, failed_num, detail))
+        })
+        .collect_vec();
+
+    if !failures.is_empty() {
+        println!();
+        println!("Failures:");
+        for (label, failed_num, detail) in failures {
+            println!("  {}: {} ({})", label, failed_num, detail);
+        }
+    }
+
+    let use_relative_score = sweep_table
+        .iter()
+        .flat_map(|(_, records)| records.iter())
+        .any(|record| record.relative_score.is_some());
+
+    // Index, number of accepted seeds and average (relative) score of the best combination.
+    let mut best: Option<(usize, usize, f64)> = None;
+    for (i, (_, records)) in sweep_table.iter().enumerate() {
+        let accepted_records = records
+            .iter()
+            .filter(|record| record.is_accepted())
+            .collect_vec();
+        let values = accepted_records
+            .iter()
+            .filter_map(|record| {
+                if use_relative_score {
+                    record.relative_score
+                } else {
+                    record.score
+                }
+            })
+            .collect_vec();
+
+        if values.is_empty() {
+            continue;
+        }
+
+        let value = statistics::mean(&values);
+        let is_better_value = |other: f64| {
+            if use_relative_score {
+                value > other
+            } else {
+                is_better(value, other, objective)
+            }
+        };
+
+        if best.is_none_or(|(_, best_accepted_num, best_value)| {
+            accepted_records.len() > best_accepted_num
+                || (accepted_records.len() == best_accepted_num && is_better_value(best_value))
+        }) {
+            best = Some((i, accepted_records.len(), value));
+        }
+    }
+
+    if let Some((i, _, value)) = best {
+        println!();
+        if use_relative_score {
+            println!(
+                "Best: {} (Avg Rel. Score = {:.3}%)",
+                sweep_table[i].0,
+                100.0 * value
+            );
+        } else {
+            println!("Best: {} (Avg Score = {:.3})", sweep_table[i].0, value);
+        }
+    }
+}
+
+/// Shows scores and execution times grouped into bins by the value of each feature.
+///
+/// If a feature takes at most `feature_bin_num` distinct values, each value forms its own bin.
+/// Otherwise, the range of the values is divided into `feature_bin_num` bins of equal width.
+fn show_feature_statistics(
+    statistics_config: &StatisticsConfig,
+    evaluation_table: &[EvaluationRecord],
+) {
+    let bin_num = statistics_config.feature_bin_num;
+    let feature_names: BTreeSet<&str> = evaluation_table
+        .iter()
+        .flat_map(|record| record.features.keys().map(String::as_str))
+        .collect();
+
+    let format_value = |value: f64| format!("{}", (value * 1000.0).round() / 1000.0);
+
+    for (i, name) in feature_names.into_iter().enumerate() {
+        let records = evaluation_table
+            .iter()
+            .filter_map(|record| Some((record.features.get(name).copied()?, record)))
+            .collect_vec();
+
+        let distinct_values = records
+            .iter()
+            .map(|&(value, _)| value)
+            .sorted_by(|x, y| x.total_cmp(y))
+            .dedup()
+            .collect_vec();
+
+        let groups = if distinct_values.len() <= bin_num {
+            distinct_values
+                .iter()
+                .map(|&value| {
+                    let bin_records = records
+                        .iter()
+                        .filter(|&&(other, _)| other == value)
+                        .map(|&(_, record)| record)
+                        .collect_vec();
+
+                    (format_value(value), bin_records)
+                })
+                .collect_vec()
+        } else {
+            let min = distinct_values[0];
+            let max = distinct_values[distinct_values.len() - 1];
+            let width = (max - min) / bin_num as f64;
+
+            let mut bins = vec![vec![]; bin_num];
+            for &(value, record) in &records {
+                let bin = (((value - min) / width) as usize).min(bin_num - 1);
+                bins[bin].push(record);
+            }
+
+            bins.into_iter()
+                .enumerate()
+                .map(|(bin, bin_records)| {
+                    let lower = min + width * bin as f64;
+                    let upper = if bin == bin_num - 1 {
+                        max
+                    } else {
+                        min + width * (bin + 1) as f64
+                    };
+                    let closing = if bin == bin_num - 1 { ']' } else { ')' };
+
+                    (
+                        format!(
+                            "[{}, {}{}",
+                            format_value(lower),
+                            format_value(upper),
+                            closing
+                        ),
+                        bin_records,
+                    )
+                })
+                .collect_vec()
+        };
+
+        if i != 0 {
+            println!();
+        }
+        println!("[Feature Statistics: {}]", name);
+        show_group_table(name, &groups);
+    }
+}
+
+/// Shows the number of accepted seeds and the averages of scores and execution times for each group of records.
+fn show_group_table(group_title: &str, groups: &[(String, Vec<&EvaluationRecord>)]) {
+    let group_width = groups
+        .iter()
+        .map(|(group, _)| group.chars().count())
+        .chain([group_title.chars().count()])
+        .max()
+        .unwrap();
+    let format_average = |values: &[f64], scale: f64, unit: &str| {
+        if values.is_empty() {
+            "-".to_owned()
+        } else {
+            format!("{:.3}{}", scale * statistics::mean(values), unit)
+        }
+    };
+
+    println!(
+        "{:<group_width$}  {:>10}  {:>14}  {:>14}  {:>10}",
+        group_title, "Accepted", "Avg Score", "Avg Rel. Score", "Avg Time"
+    );
+
+    for (group, records) in groups {
+        let accepted_records = records
+            .iter()
+            .filter(|record| record.is_accepted())
+            .collect_vec();
+
+        let scores = accepted_records
+            .iter()
+            .filter_map(|record| record.score)
+            .collect_vec();
+        let relative_scores = accepted_records
+            .iter()
+            .filter_map(|record| record.relative_score)
+            .collect_vec();
+        let exe_times = accepted_records
+            .iter()
+            .filter_map(|record| record.execution_time)
+            .collect_vec();
+
+        println!(
+            "{:<group_width$}  {:>10}  {:>14}  {:>14}  {:>10}",
+            group,
+            format!("{} / {}", accepted_records.len(), records.len()),
+            format_average(&scores, 1.0, ""),
+            format_average(&relative_scores, 100.0, "%"),
+            format_average(&exe_times, 1.0, ""),
+        );
+    }
+}
+
+/// Compares the optional values with `total_cmp`, where `None` is less than any value as in `Option::partial_cmp`.
+fn compare_optional(x: Option<f64>, y: Option<f64>) -> std::cmp::Ordering {
+    match (x, y) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        (x, y) => x.is_some().cmp(&y.is_some()),
+    }
+}
+
+/// Shows the median, dispersion, percentiles and histogram of the values.
+///
+/// `unit` is appended to each value.
+fn show_distribution(statistics_config: &StatisticsConfig, values: &[f64], unit: &str) {
+    let sorted_values = values
+        .iter()
+        .copied()
+        .sorted_by(|x, y| x.total_cmp(y))
+        .collect_vec();
+
+    let std_dev = statistics::std_dev(&sorted_values);
+    let percentiles = statistics_config
+        .percentiles
+        .iter()
+        .map(|&percentile| {
+            format!(
+                "p{} = {:.3}{}",
+                percentile,
+                statistics::quantile_of_sorted(&sorted_values, percentile / 100.0),
+                unit
+            )
+        })
+        .join(", ");
+
+    print!(
+        "\
+Median: {:.3}{unit}
+Std Dev: {:.3}{unit}
+Std Error: {:.3}{unit}
+Percentiles: {}
+Histogram: [{}] ({:.3}{unit} .. {:.3}{unit})
+",
+        statistics::quantile_of_sorted(&sorted_values, 0.5),
+        std_dev,
+        std_dev / (sorted_values.len() as f64).sqrt(),
+        percentiles,
+        statistics::sparkline(&sorted_values, statistics_config.histogram_bin_num),
+        sorted_values[0],
+        sorted_values[sorted_values.len() - 1],
+        unit = unit,
+    );
+}
+
+/// Serializes the score, writing integral values without a fractional part.
+pub fn serialize_score<S>(score: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match score {
+        Some(score) => serializer.serialize_some(&ScoreValue(*score)),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Score that is serialized without a fractional part if it is integral.
+pub struct ScoreValue(pub f64);
+
+impl Serialize for ScoreValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let score = self.0;
+
+        if score.fract() == 0.0 && score.abs() < (1_u64 << 53) as f64 {
+            serializer.serialize_i64(score as i64)
+        } else {
+            serializer.serialize_f64(score)
+        }
+    }
+}
+
+/// Serializes the tags as a space-separated list.
+fn serialize_tags<S>(tags: &[String], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(&tags.join(" "))
+}
+
+/// Deserializes the tags from a space-separated list.
+fn deserialize_tags<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let tags = <String as serde::Deserialize>::deserialize(deserializer)?;
+
+    Ok(tags.split_whitespace().map(str::to_owned).collect())
+}
+
+/// Serializes the progress as a space-separated list of `time:score`.
+fn serialize_progress<S>(progress: &[(f64, f64)], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    let progress = progress
+        .iter()
+        .map(|(time, score)| format!("{}:{}", time, score))
+        .join(" ");
+
+    serializer.serialize_str(&progress)
+}
+
+/// Deserializes the progress from a space-separated list of `time:score`.
+fn deserialize_progress<'de, D>(deserializer: D) -> Result<Vec<(f64, f64)>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let progress = <String as serde::Deserialize>::deserialize(deserializer)?;
+
+    progress
+        .split_whitespace()
+        .map(|point| {
+            let (time, score) = point.split_once(':').ok_or_else(|| {
+                serde::de::Error::custom(format!("Invalid progress point `{}`.", point))
+            })?;
+            let time = time.parse::<f64>().map_err(serde::de::Error::custom)?;
+            let score = score.parse::<f64>().map_err(serde::de::Error::custom)?;
+
+            Ok((time, score))
+        })
+        .collect()
+}
+
+/// Prefix of the names of the columns in which the features are written.
+const FEATURE_COLUMN_PREFIX: &str = "feature:";
+
+/// Prefix of the names of the columns in which the metrics are written.
+const METRIC_COLUMN_PREFIX: &str = "metric:";
+
+/// Names of the columns written for every record, in the order of `CsvRow`.
+const CSV_COLUMNS: [&str; 11] = [
+    "seed",
+    "score",
+    "relative_score",
+    "execution_time",
+    "user_time",
+    "system_time",
+    "peak_memory",
+    "status",
+    "failure",
+    "tags",
+    "progress",
+];
+
+/// Row of the CSV file, which is followed by the values of the features and the metrics in the order of the header.
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    seed: usize,
+    #[serde(serialize_with = "serialize_score")]
+    score: Option<f64>,
+    relative_score: Option<f64>,
+    execution_time: Option<f64>,
+    user_time: Option<f64>,
+    system_time: Option<f64>,
+    peak_memory: Option<u64>,
+    status: Status,
+    failure: &'a Option<String>,
+    #[serde(serialize_with = "serialize_tags")]
+    tags: &'a [String],
+    #[serde(serialize_with = "serialize_progress")]
+    progress: &'a [(f64, f64)],
+    features: Vec<Option<f64>>,
+    metrics: Vec<Option<f64>>,
+}
+
+/// Outputs score, execution time and resource usage record per seed to CSV file.
+///
+/// Each feature and metric that appears in any record is written in its own column.
 pub fn write_to_csv<P>(
     output_file_path: P,
     evaluation_table: &[EvaluationRecord],
@@ -115,12 +1109,64 @@ pub fn write_to_csv<P>(
 where
     P: AsRef<Path>,
 {
-    let mut writer = csv::Writer::from_path(&output_file_path)
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_path(&output_file_path)
         .with_context(|| "Failed to open file to output evaluation table.")?;
 
+    let feature_names: BTreeSet<&str> = evaluation_table
+        .iter()
+        .flat_map(|record| record.features.keys().map(String::as_str))
+        .collect();
+    let metric_names: BTreeSet<&str> = evaluation_table
+        .iter()
+        .flat_map(|record| record.metrics.keys().map(String::as_str))
+        .collect();
+
+    // Writes the header, which depends on the features and the metrics.
+    let header = CSV_COLUMNS
+        .iter()
+        .map(|&column| column.to_owned())
+        .chain(
+            feature_names
+                .iter()
+                .map(|name| format!("{}{}", FEATURE_COLUMN_PREFIX, name)),
+        )
+        .chain(
+            metric_names
+                .iter()
+                .map(|name| format!("{}{}", METRIC_COLUMN_PREFIX, name)),
+        )
+        .collect_vec();
+    writer
+        .write_record(&header)
+        .with_context(|| "Failed to write the header of the evaluation table.")?;
+
     for record in evaluation_table {
+        let row = CsvRow {
+            seed: record.seed,
+            score: record.score,
+            relative_score: record.relative_score,
+            execution_time: record.execution_time,
+            user_time: record.user_time,
+            system_time: record.system_time,
+            peak_memory: record.peak_memory,
+            status: record.status,
+            failure: &record.failure,
+            tags: &record.tags,
+            progress: &record.progress,
+            features: feature_names
+                .iter()
+                .map(|&name| record.features.get(name).copied())
+                .collect(),
+            metrics: metric_names
+                .iter()
+                .map(|&name| record.metrics.get(name).copied())
+                .collect(),
+        };
+
         writer
-            .serialize(record)
+            .serialize(row)
             .with_context(|| "Failed to serialize the evaluation record.")?;
     }
 
@@ -130,3 +1176,207 @@ where
 
     Ok(())
 }
+
+/// Reads the record per seed from the CSV file written by `write_to_csv`.
+pub fn read_from_csv<P>(input_file_path: P) -> anyhow::Result<Vec<EvaluationRecord>>
+where
+    P: AsRef<Path>,
+{
+    let input_file_path = input_file_path.as_ref();
+
+    let mut reader = csv::Reader::from_path(input_file_path).with_context(|| {
+        format!(
+            "Failed to open file to input evaluation table `{:?}`.",
+            input_file_path
+        )
+    })?;
+
+    let header = reader
+        .headers()
+        .with_context(|| {
+            format!(
+                "Failed to read the header of the evaluation table `{:?}`.",
+                input_file_path
+            )
+        })?
+        .clone();
+
+    // Finds the columns of the features and the metrics.
+    let named_columns = |prefix: &str| {
+        header
+            .iter()
+            .enumerate()
+            .filter_map(|(column, name)| Some((column, name.strip_prefix(prefix)?.to_owned())))
+            .collect_vec()
+    };
+    let feature_columns = named_columns(FEATURE_COLUMN_PREFIX);
+    let metric_columns = named_columns(METRIC_COLUMN_PREFIX);
+
+    let mut evaluation_table = vec![];
+
+    for row in reader.records() {
+        let row = row.with_context(|| {
+            format!(
+                "Failed to read the evaluation table `{:?}`.",
+                input_file_path
+            )
+        })?;
+
+        let mut record: EvaluationRecord = row.deserialize(Some(&header)).with_context(|| {
+            format!(
+                "Failed to deserialize the evaluation table `{:?}`.",
+                input_file_path
+            )
+        })?;
+
+        record.features = read_named_values(&row, &feature_columns, "feature")
+            .with_context(|| format!("Failed to read the features in `{:?}`.", input_file_path))?;
+        record.metrics = read_named_values(&row, &metric_columns, "metric")
+            .with_context(|| format!("Failed to read the metrics in `{:?}`.", input_file_path))?;
+
+        evaluation_table.push(record);
+    }
+
+    Ok(evaluation_table)
+}
+
+/// Reads the values in the columns of the row, skipping empty cells.
+fn read_named_values(
+    row: &csv::StringRecord,
+    columns: &[(usize, String)],
+    kind: &str,
+) -> anyhow::Result<BTreeMap<String, f64>> {
+    let mut values = BTreeMap::new();
+
+    for (column, name) in columns {
+        let value = row.get(*column).unwrap_or_default();
+        if value.is_empty() {
+            continue;
+        }
+
+        let value = value.parse::<f64>().with_context(|| {
+            format!(
+                "Failed to parse `{}` as the value of {} `{}`.",
+                value, kind, name
+            )
+        })?;
+        values.insert(name.clone(), value);
+    }
+
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    fn resource_usage() -> ResourceUsage {
+        ResourceUsage {
+            user_time: 0.5,
+            system_time: 0.25,
+            peak_memory: 1024,
+        }
+    }
+
+    #[test]
+    fn round_trip_csv() {
+        let mut accepted = EvaluationRecord::accepted(0, 1234.0, 1.5, &resource_usage());
+        accepted.relative_score = Some(0.75);
+        accepted.tags = vec!["small".to_owned(), "dense".to_owned()];
+        accepted.features = BTreeMap::from([("N".to_owned(), 10.0), ("M".to_owned(), 0.5)]);
+        accepted.metrics = BTreeMap::from([("iterations".to_owned(), 100.0)]);
+        accepted.progress = vec![(0.5, 10.0), (1.0, 1234.0)];
+
+        let mut rejected = EvaluationRecord::rejected(
+            1,
+            Status::TimeLimitExceeded,
+            "The submission exceeded the time limit, \"really\".".to_owned(),
+            2.0,
+            &resource_usage(),
+        );
+        rejected.features = BTreeMap::from([("N".to_owned(), 20.0)]);
+
+        let failed = EvaluationRecord::from_error(2, &anyhow::anyhow!("Missing input file."));
+
+        let file_path =
+            env::temp_dir().join(format!("ahc-evaluation-record-test-{}.csv", process::id()));
+        let evaluation_table = [accepted, rejected, failed];
+        write_to_csv(&file_path, &evaluation_table).unwrap();
+        let read_table = read_from_csv(&file_path);
+        let contents = fs::read_to_string(&file_path).unwrap();
+        fs::remove_file(&file_path).unwrap();
+        let read_table = read_table.unwrap();
+
+        // Each feature and metric is written in its own column.
+        let header = contents.lines().next().unwrap();
+        assert!(header.ends_with(",progress,feature:M,feature:N,metric:iterations"));
+
+        assert_eq!(read_table.len(), evaluation_table.len());
+        for (read, written) in read_table.iter().zip(&evaluation_table) {
+            assert_eq!(read.seed, written.seed);
+            assert_eq!(read.score, written.score);
+            assert_eq!(read.relative_score, written.relative_score);
+            assert_eq!(read.execution_time, written.execution_time);
+            assert_eq!(read.user_time, written.user_time);
+            assert_eq!(read.system_time, written.system_time);
+            assert_eq!(read.peak_memory, written.peak_memory);
+            assert_eq!(read.status, written.status);
+            assert_eq!(read.failure, written.failure);
+            assert_eq!(read.tags, written.tags);
+            assert_eq!(read.features, written.features);
+            assert_eq!(read.metrics, written.metrics);
+            assert_eq!(read.progress, written.progress);
+        }
+    }
+
+    #[test]
+    fn keep_measured_values_of_failed_command() {
+        let err = ExecuteCommandError {
+            seed: 3,
+            program: Program::Submission,
+            cmd_args: vec!["./a.out".to_owned()],
+            status: std::os::unix::process::ExitStatusExt::from_raw(1 << 8),
+            stdout: String::new(),
+            stderr: String::new(),
+            failure_log_file: None,
+            execution_time: 1.5,
+            resource_usage: resource_usage(),
+        };
+        let record = EvaluationRecord::from_error(3, &err.into());
+
+        assert_eq!(record.status, Status::RuntimeError);
+        assert_eq!(record.score, None);
+        assert_eq!(record.execution_time, Some(1.5));
+        assert_eq!(record.user_time, Some(0.5));
+        assert_eq!(record.system_time, Some(0.25));
+        assert_eq!(record.peak_memory, Some(1024));
+
+        let record = EvaluationRecord::from_error(4, &anyhow::anyhow!("Missing input file."));
+        assert_eq!(record.status, Status::InternalError);
+        assert_eq!(record.execution_time, None);
+        assert_eq!(record.peak_memory, None);
+    }
+
+    #[test]
+    fn write_integral_score_without_fraction() {
+        let score = serde_json::to_string(&ScoreValue(1234.0)).unwrap();
+        assert_eq!(score, "1234");
+
+        let score = serde_json::to_string(&ScoreValue(0.5)).unwrap();
+        assert_eq!(score, "0.5");
+    }
+
+    #[test]
+    fn reject_invalid_named_value() {
+        let row = csv::StringRecord::from(vec!["0", "", "abc"]);
+        let columns = [(1, "N".to_owned()), (2, "M".to_owned())];
+
+        assert!(read_named_values(&row, &columns, "feature").is_err());
+        assert_eq!(
+            read_named_values(&row, &columns[..1], "feature").unwrap(),
+            BTreeMap::new()
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! Provides a structure to retrieve the score from the output of the local tester.
+
+use std::{
+    fs::{read_to_string, remove_file},
+    io::ErrorKind,
+    process::Output,
+};
+
+use anyhow::{ensure, Context};
+use regex::Regex;
+
+use crate::config::{Config, ScoreConfig, ScoreSource, ScoreType};
+
+/// Retrieves the score according to the score configuration.
+#[derive(Debug, Clone)]
+pub struct ScoreExtractor {
+    /// Location from which the score is retrieved.
+    source: Option<ScoreSource>,
+
+    /// Regular expression for retrieving the score.
+    regex: Regex,
+
+    /// Numeric type of the score.
+    score_type: ScoreType,
+}
+
+impl ScoreExtractor {
+    /// Compiles the regular expression in the score configuration.
+    pub fn new(score_config: &ScoreConfig) -> anyhow::Result<Self> {
+        let regex = Regex::new(&score_config.pattern).with_context(|| {
+            format!(
+                "Failed to compile regular expression `{}`.",
+                score_config.pattern
+            )
+        })?;
+
+        ensure!(
+            regex.capture_names().flatten().any(|name| name == "score"),
+            "The regular expression `{}` does not have the named group `score`.",
+            score_config.pattern
+        );
+
+        Ok(Self {
+            source: score_config.source,
+            regex,
+            score_type: score_config.score_type,
+        })
+    }
+
+    /// Returns `true` if the local tester must exit successfully for the score to be retrieved.
+    pub fn requires_success(&self) -> bool {
+        self.source != Some(ScoreSource::ExitCode)
+    }
+
+    /// Removes the score file of the seed if the score is retrieved from it,
+    /// so that the score left by a previous evaluation is not retrieved.
+    pub fn remove_score_file(&self, config: &Config, seed: usize) -> anyhow::Result<()> {
+        if self.source != Some(ScoreSource::File) {
+            return Ok(());
+        }
+
+        let score_file_path = config
+            .score_file_path(seed)
+            .with_context(|| "`score.file` is not specified.")?;
+
+        match remove_file(&score_file_path) {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err)
+                .with_context(|| format!("Failed to remove score file `{:?}`.", score_file_path)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Retrieves the score for the seed from the output of the local tester.
+    ///
+    /// Returns `None` if the score is not found or is not a finite number.
+    /// In integrated mode, the standard output of the local tester is the output of the submission code,
+    /// so it is searched only if explicitly specified.
+    pub fn extract(
+        &self,
+        config: &Config,
+        seed: usize,
+        tester_output: &Output,
+    ) -> anyhow::Result<Option<f64>> {
+        let score = match self.source {
+            Some(ScoreSource::Stdout) => self.retrieve_score(&tester_output.stdout),
+            Some(ScoreSource::Stderr) => self.retrieve_score(&tester_output.stderr),
+            Some(ScoreSource::File) => {
+                let score_file_path = config
+                    .score_file_path(seed)
+                    .with_context(|| "`score.file` is not specified.")?;
+                let text = read_to_string(&score_file_path).with_context(|| {
+                    format!("Failed to read score file `{:?}`.", score_file_path)
+                })?;
+
+                self.retrieve_score(text.as_bytes())
+            }
+            Some(ScoreSource::ExitCode) => tester_output.status.code().map(|code| code as f64),
+            None if config.command.execute.integrated => self.retrieve_score(&tester_output.stderr),
+            None => self
+                .retrieve_score(&tester_output.stdout)
+                .or_else(|| self.retrieve_score(&tester_output.stderr)),
+        };
+
+        Ok(score)
+    }
+
+    /// Retrieves the score from the text with the regular expression.
+    fn retrieve_score(&self, text: &[u8]) -> Option<f64> {
+        let text = String::from_utf8_lossy(text);
+        let caps = self.regex.captures(&text)?;
+        let score = caps.name("score")?.as_str().trim();
+
+        match self.score_type {
+            ScoreType::Integer => score.parse::<i64>().ok().map(|score| score as f64),
+            ScoreType::Float => score.parse::<f64>().ok().filter(|score| score.is_finite()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, os::unix::process::ExitStatusExt, process, process::ExitStatus};
+
+    use super::*;
+    use crate::config::Objective;
+
+    /// Returns the default configuration with the score configuration replaced.
+    fn config_with_score(score_toml: &str) -> Config {
+        let mut config: Config = toml::from_str(include_str!("../../config.toml")).unwrap();
+        config.score = toml::from_str(score_toml).unwrap();
+        config
+    }
+
+    fn output(code: i32, stdout: &str, stderr: &str) -> Output {
+        Output {
+            status: ExitStatus::from_raw(code << 8),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: stderr.as_bytes().to_vec(),
+        }
+    }
+
+    fn extract(config: &Config, output: &Output) -> Option<f64> {
+        let extractor = ScoreExtractor::new(&config.score).unwrap();
+        extractor.extract(config, 0, output).unwrap()
+    }
+
+    #[test]
+    fn extract_with_pattern() {
+        let config = config_with_score("");
+        assert_eq!(
+            extract(&config, &output(0, "Score = 1234\n", "")),
+            Some(1234.0)
+        );
+        assert_eq!(extract(&config, &output(0, "", "Score = -5\n")), Some(-5.0));
+
+        // No match is regarded as not found.
+        assert_eq!(extract(&config, &output(0, "Score: 1234\n", "")), None);
+
+        let config = config_with_score(
+            r#"source = "stderr"
+pattern = 'result (?<score>\S+)'"#,
+        );
+        assert_eq!(
+            extract(&config, &output(0, "result 1\n", "result 2\n")),
+            Some(2.0)
+        );
+    }
+
+    #[test]
+    fn reject_pattern_without_score_group() {
+        let score_config: ScoreConfig = toml::from_str(r#"pattern = 'Score = (\d+)'"#).unwrap();
+        assert!(ScoreExtractor::new(&score_config).is_err());
+    }
+
+    #[test]
+    fn extract_by_type() {
+        let config = config_with_score(r#"pattern = 'Score = (?<score>\S+)'"#);
+        assert_eq!(
+            extract(&config, &output(0, "Score = 9223372036854775807\n", "")),
+            Some(i64::MAX as f64)
+        );
+
+        // An integer that overflows or has a fraction is not found.
+        assert_eq!(
+            extract(&config, &output(0, "Score = 9223372036854775808\n", "")),
+            None
+        );
+        assert_eq!(extract(&config, &output(0, "Score = 1.5\n", "")), None);
+
+        let config = config_with_score(
+            r#"pattern = 'Score = (?<score>\S+)'
+type = "float""#,
+        );
+        assert_eq!(
+            extract(&config, &output(0, "Score = 1.5e3\n", "")),
+            Some(1500.0)
+        );
+
+        // A score that is not a finite number is not found.
+        assert_eq!(extract(&config, &output(0, "Score = inf\n", "")), None);
+        assert_eq!(extract(&config, &output(0, "Score = NaN\n", "")), None);
+    }
+
+    #[test]
+    fn extract_from_exit_code() {
+        let config = config_with_score(r#"source = "exit-code""#);
+        let extractor = ScoreExtractor::new(&config.score).unwrap();
+
+        assert!(!extractor.requires_success());
+        assert_eq!(extract(&config, &output(42, "Score = 1\n", "")), Some(42.0));
+    }
+
+    #[test]
+    fn extract_from_file() {
+        let file_path = env::temp_dir().join(format!(
+            "ahc-evaluation-score-test-{}-{{seed}}.txt",
+            process::id()
+        ));
+        let config = config_with_score(&format!(
+            "source = \"file\"\nfile = '{}'",
+            file_path.to_str().unwrap()
+        ));
+        let extractor = ScoreExtractor::new(&config.score).unwrap();
+        let score_file_path = config.score_file_path(0).unwrap();
+
+        fs::write(&score_file_path, "Score = 77\n").unwrap();
+        let score = extractor.extract(&config, 0, &output(0, "Score = 1\n", ""));
+
+        // The score file is removed so that a stale score is not retrieved.
+        extractor.remove_score_file(&config, 0).unwrap();
+        let exists = score_file_path.exists();
+        let missing = extractor.extract(&config, 0, &output(0, "", ""));
+
+        assert_eq!(score.unwrap(), Some(77.0));
+        assert!(!exists);
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn read_type_and_objective() {
+        let score_config: ScoreConfig = toml::from_str("").unwrap();
+        assert_eq!(score_config.score_type, ScoreType::Integer);
+        assert_eq!(score_config.objective, Objective::Maximize);
+
+        let score_config: ScoreConfig =
+            toml::from_str("type = \"float\"\nobjective = \"minimize\"").unwrap();
+        assert_eq!(score_config.score_type, ScoreType::Float);
+        assert_eq!(score_config.objective, Objective::Minimize);
+
+        assert!(toml::from_str::<ScoreConfig>("objective = \"max\"").is_err());
+    }
+}
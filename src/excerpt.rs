@@ -0,0 +1,75 @@
+//! Provides the excerpt of the output of a command shown in error messages.
+
+/// Returns the bytes decoded lossily as UTF-8, keeping only the first `head` bytes and the last `tail` bytes.
+///
+/// If bytes are omitted, a note with the number of omitted bytes is inserted between the head and the tail.
+/// The boundaries are moved so as not to split a multi-byte character.
+pub fn excerpt(bytes: &[u8], head: usize, tail: usize) -> String {
+    if bytes.len() <= head.saturating_add(tail) {
+        return String::from_utf8_lossy(bytes).into_owned();
+    }
+
+    // Moves the end of the head and the start of the tail back to the start of a character.
+    let head_end = char_boundary(bytes, head);
+    let tail_start = char_boundary(bytes, bytes.len() - tail);
+
+    format!(
+        "{}\n... ({} bytes omitted) ...\n{}",
+        String::from_utf8_lossy(&bytes[..head_end]),
+        tail_start - head_end,
+        String::from_utf8_lossy(&bytes[tail_start..]),
+    )
+}
+
+/// Returns the position at most 3 bytes before `index` that is not in the middle of a UTF-8 character.
+fn char_boundary(bytes: &[u8], index: usize) -> usize {
+    // A continuation byte of UTF-8 has the form `0b10xxxxxx`.
+    let is_continuation = |i: usize| bytes.get(i).is_some_and(|&byte| byte & 0xC0 == 0x80);
+
+    (index.saturating_sub(3)..=index)
+        .rev()
+        .find(|&i| !is_continuation(i))
+        .unwrap_or(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keep_short_output_whole() {
+        assert_eq!(excerpt(b"hello", 3, 2), "hello");
+        assert_eq!(excerpt(b"", 0, 0), "");
+        assert_eq!(excerpt(b"abc", usize::MAX, usize::MAX), "abc");
+    }
+
+    #[test]
+    fn omit_middle_of_long_output() {
+        assert_eq!(
+            excerpt(b"0123456789", 3, 2),
+            "012\n... (5 bytes omitted) ...\n89"
+        );
+        assert_eq!(
+            excerpt(b"0123456789", 0, 0),
+            "\n... (10 bytes omitted) ...\n"
+        );
+    }
+
+    #[test]
+    fn do_not_split_multi_byte_characters() {
+        // Each `あ` is 3 bytes long.
+        let text = "あいうえお".as_bytes();
+
+        assert_eq!(excerpt(text, 4, 4), "あ\n... (6 bytes omitted) ...\nえお");
+        assert_eq!(excerpt(text, 6, 3), "あい\n... (6 bytes omitted) ...\nお");
+    }
+
+    #[test]
+    fn decode_invalid_utf8_lossily() {
+        assert_eq!(excerpt(b"a\xffb", 10, 10), "a\u{fffd}b");
+        assert_eq!(
+            excerpt(b"\xff\xfe0123456789\xff", 2, 1),
+            "\u{fffd}\u{fffd}\n... (10 bytes omitted) ...\n\u{fffd}"
+        );
+    }
+}
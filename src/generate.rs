@@ -0,0 +1,337 @@
+//! Provides the generation of the missing input files with the generator.
+
+use std::{
+    fs::{create_dir_all, read, remove_dir_all, write},
+    path::Path,
+    process::Stdio,
+};
+
+use anyhow::{bail, ensure, Context};
+use itertools::Itertools;
+use rayon::{iter::IntoParallelRefIterator, prelude::ParallelIterator};
+
+use crate::{config::Config, seed::SeedEntry};
+
+/// Generates the input files that do not exist for the seeds.
+///
+/// Nothing is done if `command.generate` is not specified.
+///
+/// # Errors
+///
+/// If the generator fails or an input file is still missing after the generation, an error is generated.
+pub fn generate_inputs(config: &Config, seed_entries: &[SeedEntry]) -> anyhow::Result<()> {
+    let Some(cmd_args) = &config.command.generate else {
+        return Ok(());
+    };
+
+    let missing_entries = seed_entries
+        .iter()
+        .filter(|seed_entry| !config.input_file_path(seed_entry.seed).exists())
+        .unique_by(|seed_entry| seed_entry.seed)
+        .collect_vec();
+
+    if missing_entries.is_empty() {
+        return Ok(());
+    }
+
+    println!("Generating {} input files...", missing_entries.len());
+
+    if cmd_args.iter().any(|arg| arg.contains("{seeds-file}")) {
+        generate_in_batch(config, &missing_entries)?;
+    } else {
+        missing_entries
+            .par_iter()
+            .try_for_each(|seed_entry| generate_for_seed(config, seed_entry))?;
+    }
+
+    let still_missing_seeds = missing_entries
+        .iter()
+        .map(|seed_entry| seed_entry.seed)
+        .filter(|&seed| !config.input_file_path(seed).exists())
+        .collect_vec();
+
+    ensure!(
+        still_missing_seeds.is_empty(),
+        "The generator did not create the input files for seeds {}.",
+        still_missing_seeds.iter().join(", ")
+    );
+
+    Ok(())
+}
+
+/// Executes the generator for the seed and saves its standard output as the input file
+/// unless the generator has created the input file or printed nothing.
+fn generate_for_seed(config: &Config, seed_entry: &SeedEntry) -> anyhow::Result<()> {
+    let seed = seed_entry.seed;
+    let input_file_path = config.input_file_path(seed);
+
+    let command_spec = config.command_for_generate(seed, &seed_entry.params)?;
+    let cmd_args = &command_spec.cmd_args;
+    let output = command_spec
+        .to_command()?
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| {
+            format!(
+                "Failed to start the generator for seed {}.\nList of arguments: {:?}",
+                seed, cmd_args
+            )
+        })?;
+
+    ensure!(
+        output.status.success(),
+        "The generator exited with {} for seed {}.\nList of arguments: {:?}",
+        output.status,
+        seed,
+        cmd_args
+    );
+
+    if !input_file_path.exists() && !output.stdout.is_empty() {
+        save_input_file(&input_file_path, &output.stdout)?;
+    }
+
+    Ok(())
+}
+
+/// Executes the generator once with a temporary file listing the missing seeds.
+///
+/// Files in the temporary directory `{gen-dir}` are copied to the input files of the seeds on the corresponding lines.
+fn generate_in_batch(config: &Config, missing_entries: &[&SeedEntry]) -> anyhow::Result<()> {
+    let temp_dir = std::env::temp_dir().join(format!("ahc-evaluation-{}", std::process::id()));
+    let seeds_file_path = temp_dir.join("seeds.txt");
+    let gen_dir = temp_dir.join("in");
+
+    // Lists the values of the parameters in the order of their names, which must be the same for all seeds.
+    let sorted_params = |seed_entry: &SeedEntry| {
+        seed_entry
+            .params
+            .iter()
+            .cloned()
+            .sorted_by(|(name_a, _), (name_b, _)| name_a.cmp(name_b))
+            .collect_vec()
+    };
+    let param_names = |seed_entry: &SeedEntry| {
+        sorted_params(seed_entry)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect_vec()
+    };
+    let first_param_names = param_names(missing_entries[0]);
+    if let Some(seed_entry) = missing_entries
+        .iter()
+        .find(|seed_entry| param_names(seed_entry) != first_param_names)
+    {
+        bail!(
+            "Seed {} has parameters [{}] unlike seed {} with [{}], which is not allowed with `{{seeds-file}}`.",
+            seed_entry.seed,
+            param_names(seed_entry).join(", "),
+            missing_entries[0].seed,
+            first_param_names.join(", ")
+        );
+    }
+
+    let seeds_text = missing_entries
+        .iter()
+        .map(|seed_entry| {
+            std::iter::once(seed_entry.seed.to_string())
+                .chain(
+                    sorted_params(seed_entry)
+                        .into_iter()
+                        .map(|(_, value)| value),
+                )
+                .join(" ")
+                + "\n"
+        })
+        .join("");
+    create_dir_all(&gen_dir).with_context(|| {
+        format!(
+            "Failed to create temporary directory `{:?}` for the generator.",
+            gen_dir
+        )
+    })?;
+
+    write(&seeds_file_path, seeds_text)
+        .with_context(|| format!("Failed to write seed file `{:?}`.", seeds_file_path))?;
+
+    let result = run_batch_generator(config, &seeds_file_path, &gen_dir).and_then(|()| {
+        for (i, seed_entry) in missing_entries.iter().enumerate() {
+            let generated_file_path = gen_dir.join(format!("{:04}.txt", i));
+            if !generated_file_path.exists() {
+                continue;
+            }
+
+            let text = read(&generated_file_path).with_context(|| {
+                format!("Failed to read generated file `{:?}`.", generated_file_path)
+            })?;
+            save_input_file(&config.input_file_path(seed_entry.seed), &text)?;
+        }
+
+        Ok(())
+    });
+
+    // Removes the temporary directory regardless of the result.
+    let _ = remove_dir_all(&temp_dir);
+
+    result
+}
+
+/// Executes the generator with the placeholders `{seeds-file}` and `{gen-dir}` replaced.
+fn run_batch_generator(
+    config: &Config,
+    seeds_file_path: &Path,
+    gen_dir: &Path,
+) -> anyhow::Result<()> {
+    let command_spec = config.command_for_generate_batch(seeds_file_path, gen_dir);
+    let cmd_args = &command_spec.cmd_args;
+    let status = command_spec
+        .to_command()?
+        .stdin(Stdio::null())
+        .status()
+        .with_context(|| {
+            format!(
+                "Failed to start the generator.\nList of arguments: {:?}",
+                cmd_args
+            )
+        })?;
+
+    ensure!(
+        status.success(),
+        "The generator exited with {}.\nList of arguments: {:?}",
+        status,
+        cmd_args
+    );
+
+    Ok(())
+}
+
+/// Saves the text as the input file, creating its directory if necessary.
+fn save_input_file(input_file_path: &Path, text: &[u8]) -> anyhow::Result<()> {
+    if let Some(input_dir) = input_file_path.parent() {
+        create_dir_all(input_dir)
+            .with_context(|| format!("Failed to create input directory `{:?}`.", input_dir))?;
+    }
+
+    write(input_file_path, text)
+        .with_context(|| format!("Failed to write input file `{:?}`.", input_file_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, env, fs::read_to_string, path::PathBuf, process};
+
+    use super::*;
+
+    /// Returns the default configuration with the generator and a temporary input directory.
+    fn config_with_generator(name: &str, cmd_args: &[&str]) -> Config {
+        let mut config: Config = toml::from_str(include_str!("../config.toml")).unwrap();
+        config.path.input_dir = env::temp_dir().join(format!(
+            "ahc-evaluation-generate-test-{}-{}",
+            process::id(),
+            name
+        ));
+        config.command.generate = Some(cmd_args.iter().map(|arg| arg.to_string()).collect());
+        config
+    }
+
+    fn seed_entry(seed: usize, params: &[(&str, &str)]) -> SeedEntry {
+        SeedEntry {
+            seed,
+            tags: vec![],
+            params: params
+                .iter()
+                .map(|&(name, value)| (name.to_owned(), value.to_owned()))
+                .collect(),
+        }
+    }
+
+    fn batch_temp_dir() -> PathBuf {
+        env::temp_dir().join(format!("ahc-evaluation-{}", process::id()))
+    }
+
+    #[test]
+    fn generate_for_each_seed() {
+        let mut config = config_with_generator(
+            "per-seed",
+            &[
+                "sh",
+                "-c",
+                "if [ {seed} = 5 ]; then echo own > {input}; else echo {seed} {param.A} $GEN_B; fi",
+            ],
+        );
+        config.command.generate_env =
+            BTreeMap::from([("GEN_B".to_owned(), "{param.B}".to_owned())]);
+        let seed_entries = [
+            seed_entry(3, &[("B", "x"), ("A", "1")]),
+            seed_entry(5, &[("A", "2"), ("B", "y")]),
+            seed_entry(7, &[("A", "3"), ("B", "z")]),
+        ];
+
+        // The existing input file is kept.
+        save_input_file(&config.input_file_path(7), b"existing\n").unwrap();
+
+        let result = generate_inputs(&config, &seed_entries);
+        let texts = seed_entries
+            .iter()
+            .map(|seed_entry| read_to_string(config.input_file_path(seed_entry.seed)).unwrap())
+            .collect_vec();
+        remove_dir_all(&config.path.input_dir).unwrap();
+
+        result.unwrap();
+        assert_eq!(texts, ["3 1 x\n", "own\n", "existing\n"]);
+    }
+
+    #[test]
+    fn reject_missing_parameter() {
+        let config = config_with_generator("missing-param", &["echo", "{param.A}"]);
+        let result = generate_inputs(&config, &[seed_entry(0, &[])]);
+        let _ = remove_dir_all(&config.path.input_dir);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn generate_in_batch_with_seeds_file() {
+        let config = config_with_generator(
+            "batch",
+            &[
+                "sh",
+                "-c",
+                "i=0; while read line; do echo \"$line\" > {gen-dir}/$(printf %04d $i).txt; i=$((i + 1)); done < {seeds-file}",
+            ],
+        );
+        let seed_entries = [
+            seed_entry(3, &[("B", "x"), ("A", "1")]),
+            seed_entry(5, &[("A", "2"), ("B", "y")]),
+        ];
+
+        let result = generate_inputs(&config, &seed_entries);
+        let texts = seed_entries
+            .iter()
+            .map(|seed_entry| read_to_string(config.input_file_path(seed_entry.seed)).unwrap())
+            .collect_vec();
+        remove_dir_all(&config.path.input_dir).unwrap();
+
+        // The parameters are written in the order of their names, and the temporary directory is removed.
+        result.unwrap();
+        assert_eq!(texts, ["3 1 x\n", "5 2 y\n"]);
+        assert!(!batch_temp_dir().exists());
+
+        // The temporary directory is removed even if the generator fails.
+        let config = config_with_generator(
+            "batch-failure",
+            &["sh", "-c", "cat {seeds-file} > /dev/null; exit 1"],
+        );
+        let result = generate_inputs(&config, &seed_entries);
+
+        assert!(result.is_err());
+        assert!(!batch_temp_dir().exists());
+
+        // The parameters must have the same names for all seeds.
+        let result = generate_inputs(
+            &config,
+            &[seed_entry(3, &[("A", "1")]), seed_entry(5, &[("B", "2")])],
+        );
+        assert!(result.is_err());
+    }
+}
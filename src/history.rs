@@ -0,0 +1,241 @@
+//! Provides a store that saves every evaluation as a run in a directory of JSON files.
+
+use std::{
+    fs::{create_dir_all, read, read_to_string, write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::{bail, Context};
+use chrono::Local;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    config::Config,
+    evaluation::{self, EvaluationRecord},
+};
+
+/// Extension of the files in which runs are saved.
+const RUN_FILE_EXTENSION: &str = "json";
+
+/// Information about the circumstances in which a run was executed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunMetadata {
+    /// Identifier of the run, which is also the stem of the file name.
+    pub id: String,
+
+    /// Time at which the run was started, in RFC 3339 format.
+    pub timestamp: String,
+
+    /// Label specified by the user.
+    pub label: Option<String>,
+
+    /// SHA-256 hash of the configuration file.
+    pub config_hash: String,
+
+    /// Command line arguments to execute the submission code.
+    pub submission_command: Vec<String>,
+
+    /// Commit of the git repository in the current directory.
+    pub git_commit: Option<String>,
+
+    /// Whether the git repository has uncommitted changes.
+    pub git_dirty: Option<bool>,
+
+    /// Name of the host on which the run was executed.
+    pub hostname: Option<String>,
+
+    /// Number of threads used for evaluation.
+    pub thread_num: usize,
+}
+
+impl RunMetadata {
+    /// Collects the metadata of the run that is about to start.
+    pub fn collect<P>(
+        config: &Config,
+        config_file_path: P,
+        label: Option<String>,
+    ) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let now = Local::now();
+
+        let config_bytes = read(config_file_path.as_ref())
+            .with_context(|| "Failed to read configuration file.")?;
+        let config_hash = Sha256::digest(config_bytes)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .join("");
+
+        // Appends a suffix to the identifier if a run with the same identifier already exists.
+        let base_id = now.format("%Y%m%d-%H%M%S").to_string();
+        let mut id = base_id.clone();
+        if let Some(history_dir) = &config.path.history_dir {
+            for suffix in 1.. {
+                if !run_file_path(history_dir, &id).exists() {
+                    break;
+                }
+
+                id = format!("{}-{}", base_id, suffix);
+            }
+        }
+
+        let git_commit = git_commit();
+        let git_dirty = run_git(&["status", "--porcelain"]).map(|status| !status.is_empty());
+
+        Ok(Self {
+            id,
+            timestamp: now.to_rfc3339(),
+            label,
+            config_hash,
+            submission_command: config.command.execute.submission.clone(),
+            git_commit,
+            git_dirty,
+            hostname: hostname(),
+            thread_num: rayon::current_num_threads(),
+        })
+    }
+}
+
+/// Evaluation saved in the history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Run {
+    /// Information about the circumstances in which the run was executed.
+    pub metadata: RunMetadata,
+
+    /// Evaluation record for each seed.
+    pub records: Vec<EvaluationRecord>,
+}
+
+impl Run {
+    /// Saves the run in the history directory and returns the path of the file.
+    pub fn save<P>(&self, history_dir: P) -> anyhow::Result<PathBuf>
+    where
+        P: AsRef<Path>,
+    {
+        let history_dir = history_dir.as_ref();
+
+        create_dir_all(history_dir).with_context(|| "Failed to create history directory.")?;
+
+        let file_path = run_file_path(history_dir, &self.metadata.id);
+        let json =
+            serde_json::to_string_pretty(self).with_context(|| "Failed to serialize run.")?;
+        write(&file_path, json)
+            .with_context(|| format!("Failed to write run to `{:?}`.", file_path))?;
+
+        Ok(file_path)
+    }
+
+    /// Reads the run from the file.
+    pub fn read_from_file<P>(file_path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let file_path = file_path.as_ref();
+
+        let json = read_to_string(file_path)
+            .with_context(|| format!("Failed to read run file `{:?}`.", file_path))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("Failed to deserialize run file `{:?}`.", file_path))
+    }
+}
+
+/// Reads the evaluation records of the run specified by `run`.
+///
+/// `run` is either the path of a CSV file written by `write_to_csv`,
+/// the path of a run file, or the identifier of a run saved in the history directory.
+pub fn read_records(
+    run: &str,
+    history_dir: Option<&Path>,
+) -> anyhow::Result<Vec<EvaluationRecord>> {
+    let path = Path::new(run);
+
+    if path.is_file() {
+        return match path.extension().and_then(|extension| extension.to_str()) {
+            Some("csv") => evaluation::read_from_csv(path),
+            _ => Ok(Run::read_from_file(path)?.records),
+        };
+    }
+
+    let Some(history_dir) = history_dir else {
+        bail!(
+            "`{}` is not a file, and the history directory is not configured.",
+            run
+        );
+    };
+
+    let file_path = run_file_path(history_dir, run);
+    if !file_path.is_file() {
+        bail!("Run `{}` was not found in `{:?}`.", run, history_dir);
+    }
+
+    Ok(Run::read_from_file(file_path)?.records)
+}
+
+/// Returns the path of the file in which the run with the identifier is saved.
+///
+/// The extension is appended to the identifier, which may itself contain dots like `v1.2`.
+pub fn run_file_path<P>(history_dir: P, id: &str) -> PathBuf
+where
+    P: AsRef<Path>,
+{
+    history_dir
+        .as_ref()
+        .join(format!("{}.{}", id, RUN_FILE_EXTENSION))
+}
+
+/// Returns the commit of the git repository in the current directory.
+pub fn git_commit() -> Option<String> {
+    run_git(&["rev-parse", "HEAD"])
+}
+
+/// Executes git with the arguments and returns the trimmed standard output.
+///
+/// Returns `None` if git is not available or the current directory is not a git repository.
+fn run_git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Returns the name of the host.
+fn hostname() -> Option<String> {
+    let mut buf = [0_u8; 256];
+    let ret = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) };
+
+    if ret != 0 {
+        return None;
+    }
+
+    let len = buf.iter().position(|&byte| byte == 0).unwrap_or(buf.len());
+    Some(String::from_utf8_lossy(&buf[..len]).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keep_dots_in_run_identifier() {
+        assert_eq!(
+            run_file_path("history", "v1.2"),
+            Path::new("history/v1.2.json")
+        );
+        assert_eq!(
+            run_file_path("history", "20240101-000000"),
+            Path::new("history/20240101-000000.json")
+        );
+    }
+}
@@ -1,4 +1,14 @@
 pub mod arguments;
 pub mod build;
+pub mod compare;
 pub mod config;
 pub mod evaluation;
+pub mod excerpt;
+pub mod generate;
+pub mod history;
+pub mod random;
+pub mod seed;
+pub mod statistics;
+pub mod sweep;
+pub mod template;
+pub mod tune;
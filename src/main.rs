@@ -1,67 +1,423 @@
-use std::fs::read_to_string;
+use std::{
+    fs::{create_dir_all, remove_dir_all, remove_file, write},
+    path::Path,
+};
 
-use ahc_evaluation::{arguments::Arguments, build, config::Config, evaluation};
+use ahc_evaluation::{
+    arguments::{Arguments, Command, RunArguments, SearchArguments, SeedArguments},
+    build, compare,
+    config::Config,
+    evaluation::{self, EvaluationRecord},
+    generate,
+    history::{self, Run, RunMetadata},
+    seed::{self, SeedEntry},
+    sweep, tune,
+};
 use anyhow::{ensure, Context};
 use clap::Parser;
+use itertools::Itertools;
 use rayon::ThreadPoolBuilder;
 
+/// Default configuration file written by `init`.
+const DEFAULT_CONFIG: &str = include_str!("../config.toml");
+
 fn main() -> anyhow::Result<()> {
     // Parses command line arguments.
     let args = Arguments::parse();
 
+    // Evaluates the submission code with the default options if no subcommand is specified.
+    let command = args
+        .command
+        .unwrap_or(Command::Run(RunArguments::default()));
+
+    // Writes the configuration file before it is read.
+    if let Command::Init { force } = command {
+        return init(&args.config, force);
+    }
+
     // Reads the configuration from a file.
-    let config = Config::read_from_file(args.config)?;
+    let config = Config::read_from_file(&args.config)?;
 
-    // Sets the number of threads to be used in the rayon thread pool.
-    if let Some(thread_num) = config.thread.thread_num {
-        ThreadPoolBuilder::new()
-            .num_threads(thread_num)
-            .build_global()
-            .with_context(|| "Failed to set the number of threads.")?;
+    match command {
+        Command::Run(run_args) => run(&config, &args.config, run_args),
+        Command::Build => build_programs(&config),
+        Command::Report { run } => report(&config, run.as_deref()),
+        Command::Compare { run_a, run_b } => compare(&config, &run_a, &run_b),
+        Command::Sweep(search_args) => sweep(&config, search_args),
+        Command::Tune(search_args) => tune(&config, search_args),
+        Command::Clean => clean(&config),
+        Command::Init { .. } => unreachable!(),
     }
+}
 
-    // Reads the seed list from a file.
-    let seeds = read_seed_from_file(&config)?;
+/// Builds the programs, evaluates the submission code for each seed and shows the statistics.
+fn run(config: &Config, config_file_path: &str, run_args: RunArguments) -> anyhow::Result<()> {
+    // Sets the number of threads to be used in the rayon thread pool.
+    set_thread_num(config)?;
 
-    // Returns an error if the seed list is empty.
-    ensure!(!seeds.is_empty(), "Seed list is empty.");
+    // Reads the seed list.
+    let seed_entries = select_seeds(config, &run_args.seeds)?;
+
+    // Collects the metadata of the run before the evaluation starts.
+    let run_metadata = RunMetadata::collect(config, config_file_path, run_args.label)?;
+
+    // Makes the identifier of the run available as the placeholder `{run-id}`.
+    let config = &Config {
+        run_id: Some(run_metadata.id.clone()),
+        ..config.clone()
+    };
 
     // Builds the local tester.
-    build::build_tester(&config)?;
+    if !run_args.no_build {
+        build::build_tester(config)?;
+    }
+
+    // Generates the missing input files.
+    generate::generate_inputs(config, &seed_entries)?;
 
     // Builds the submission code.
-    build::build_submission(&config)?;
+    if !run_args.no_build {
+        build::build_submission(config)?;
+    }
 
     // Executes the local tester and retrieve evaluations.
-    let evaluation_table = evaluation::evaluate(&config, &seeds)?;
+    let mut evaluation_table = evaluation::evaluate(config, &seed_entries)?;
 
-    // Shows statistics about scores and execution times.
-    evaluation::show_statistics(&evaluation_table)?;
+    // Updates the best known scores and calculates relative scores.
+    if let Some(best_score_file_path) = &config.path.best_score {
+        evaluation::update_best_scores(
+            best_score_file_path,
+            config.score.objective,
+            &mut evaluation_table,
+        )?;
+
+        // Saves the relative scores in the artifacts as well.
+        evaluation::update_artifact_records(config, &evaluation_table)?;
+    }
+
+    // Saves the evaluation as a run in the history.
+    if let Some(history_dir) = &config.path.history_dir {
+        let run = Run {
+            metadata: run_metadata,
+            records: evaluation_table.clone(),
+        };
+        run.save(history_dir)?;
+    }
 
     // Outputs score and execution time record per seed to CSV file.
     evaluation::write_to_csv(&config.path.evaluation_record, &evaluation_table)?;
 
+    show_report(config, &evaluation_table)
+}
+
+/// Evaluates the submission code for every combination of the parameters and shows the averages for each.
+fn sweep(config: &Config, search_args: SearchArguments) -> anyhow::Result<()> {
+    ensure!(
+        !config.params.is_empty(),
+        "No parameters are declared with `[[param]]` in the configuration file."
+    );
+
+    // Sets the number of threads to be used in the rayon thread pool.
+    set_thread_num(config)?;
+
+    // Reads the seed list.
+    let seed_entries = select_seeds(config, &search_args.seeds)?;
+
+    // Builds the local tester.
+    if !search_args.no_build {
+        build::build_tester(config)?;
+    }
+
+    // Generates the missing input files.
+    generate::generate_inputs(config, &seed_entries)?;
+
+    // Builds the submission code.
+    if !search_args.no_build {
+        build::build_submission(config)?;
+    }
+
+    // Evaluates the submission code with each combination of the parameters.
+    let combinations = sweep::combinations(&config.params)?;
+    let mut labels = vec![];
+    let mut evaluation_table = vec![];
+    for (i, param_values) in combinations.iter().enumerate() {
+        let label = sweep::format_combination(param_values);
+        println!("[{}/{}] {}", i + 1, combinations.len(), label);
+
+        // Failed seeds are recorded instead of stopping the sweep.
+        // The artifacts of each combination are saved in its own subdirectory.
+        let mut config = Config {
+            param_values: param_values.clone(),
+            artifact_subdir: Some(sweep::combination_dir_name(param_values)),
+            ..config.clone()
+        };
+        config.command.execute.keep_going = true;
+        evaluation_table.extend(evaluation::evaluate(&config, &seed_entries)?);
+        labels.push(label);
+    }
+
+    // Calculates relative scores against the best known scores including all combinations,
+    // leaving the best score file unchanged since most combinations are not to be adopted.
+    if let Some(best_score_file_path) = &config.path.best_score {
+        evaluation::calculate_relative_scores(
+            best_score_file_path,
+            config.score.objective,
+            &mut evaluation_table,
+        )?;
+
+        // Saves the relative scores in the artifacts of each combination as well.
+        for (param_values, records) in combinations
+            .iter()
+            .zip(evaluation_table.chunks(seed_entries.len()))
+        {
+            let config = Config {
+                artifact_subdir: Some(sweep::combination_dir_name(param_values)),
+                ..config.clone()
+            };
+            evaluation::update_artifact_records(&config, records)?;
+        }
+    }
+
+    let sweep_table = labels
+        .into_iter()
+        .zip(evaluation_table.chunks(seed_entries.len()))
+        .collect_vec();
+
+    println!();
+    evaluation::show_sweep_statistics(config.score.objective, &sweep_table);
+
+    Ok(())
+}
+
+/// Searches for the best values of the parameters and shows the statistics of the best.
+fn tune(config: &Config, search_args: SearchArguments) -> anyhow::Result<()> {
+    ensure!(
+        !config.params.is_empty(),
+        "No parameters are declared with `[[param]]` in the configuration file."
+    );
+
+    // Sets the number of threads to be used in the rayon thread pool.
+    set_thread_num(config)?;
+
+    // Reads the seed list.
+    let seed_entries = select_seeds(config, &search_args.seeds)?;
+
+    // Builds the local tester.
+    if !search_args.no_build {
+        build::build_tester(config)?;
+    }
+
+    // Generates the missing input files.
+    generate::generate_inputs(config, &seed_entries)?;
+
+    // Builds the submission code.
+    if !search_args.no_build {
+        build::build_submission(config)?;
+    }
+
+    tune::tune(config, &seed_entries)
+}
+
+/// Builds the local tester and the submission code.
+fn build_programs(config: &Config) -> anyhow::Result<()> {
+    // Builds the local tester.
+    build::build_tester(config)?;
+
+    // Builds the submission code.
+    build::build_submission(config)?;
+
+    Ok(())
+}
+
+/// Shows the statistics of a stored evaluation.
+fn report(config: &Config, run: Option<&str>) -> anyhow::Result<()> {
+    let evaluation_table = match run {
+        Some(run) => history::read_records(run, config.path.history_dir.as_deref())?,
+        None => evaluation::read_from_csv(&config.path.evaluation_record)?,
+    };
+
+    show_report(config, &evaluation_table)
+}
+
+/// Compares two runs seed by seed.
+fn compare(config: &Config, run_a: &str, run_b: &str) -> anyhow::Result<()> {
+    let history_dir = config.path.history_dir.as_deref();
+    let records_a = history::read_records(run_a, history_dir)?;
+    let records_b = history::read_records(run_b, history_dir)?;
+
+    compare::compare_runs(
+        config.score.objective,
+        (run_a, &records_a),
+        (run_b, &records_b),
+    )
+}
+
+/// Writes the default configuration file.
+fn init(config_file_path: &str, force: bool) -> anyhow::Result<()> {
+    let config_file_path = Path::new(config_file_path);
+
+    ensure!(
+        force || !config_file_path.exists(),
+        "Configuration file `{:?}` already exists. Use `--force` to overwrite it.",
+        config_file_path
+    );
+
+    if let Some(parent_dir) = config_file_path.parent() {
+        create_dir_all(parent_dir).with_context(|| {
+            format!(
+                "Failed to create the directory of `{:?}`.",
+                config_file_path
+            )
+        })?;
+    }
+
+    write(config_file_path, DEFAULT_CONFIG).with_context(|| {
+        format!(
+            "Failed to write configuration file `{:?}`.",
+            config_file_path
+        )
+    })?;
+
+    println!("Created `{}`.", config_file_path.display());
+
     Ok(())
 }
 
-/// Reads the seed list from a file.
+/// Removes the output files, the files saved for each seed in the seed file and the CSV file of evaluation records.
+fn clean(config: &Config) -> anyhow::Result<()> {
+    let output_dir = &config.path.output_dir;
+    if output_dir.exists() {
+        remove_dir_all(output_dir)
+            .with_context(|| format!("Failed to remove output directory `{:?}`.", output_dir))?;
+        println!("Removed `{}`.", output_dir.display());
+    }
+
+    let evaluation_record = &config.path.evaluation_record;
+    if evaluation_record.exists() {
+        remove_file(evaluation_record).with_context(|| {
+            format!(
+                "Failed to remove evaluation record `{:?}`.",
+                evaluation_record
+            )
+        })?;
+        println!("Removed `{}`.", evaluation_record.display());
+    }
+
+    // Removes the standard error outputs, the logs, the failure logs and the artifacts of the seeds.
+    let mut removed_num = 0;
+    for seed_entry in seed::read_seed_from_file(&config.path.seed_file)? {
+        let seed = seed_entry.seed;
+
+        let file_paths = [
+            config.stderr_file_path(seed),
+            config.log_file_path(seed),
+            config.failure_log_file_path(seed),
+        ];
+        for file_path in file_paths.into_iter().flatten() {
+            if file_path.is_file() {
+                remove_file(&file_path)
+                    .with_context(|| format!("Failed to remove `{:?}`.", file_path))?;
+                removed_num += 1;
+            }
+        }
+
+        if let Some(dir) = config.artifact_dir_path(seed) {
+            if dir.is_dir() {
+                remove_dir_all(&dir)
+                    .with_context(|| format!("Failed to remove artifact directory `{:?}`.", dir))?;
+                removed_num += 1;
+            }
+        }
+    }
+    if removed_num > 0 {
+        println!(
+            "Removed {} files and directories saved for the seeds.",
+            removed_num
+        );
+    }
+
+    Ok(())
+}
+
+/// Sets the number of threads to be used in the rayon thread pool.
+fn set_thread_num(config: &Config) -> anyhow::Result<()> {
+    if let Some(thread_num) = config.thread.thread_num {
+        ThreadPoolBuilder::new()
+            .num_threads(thread_num)
+            .build_global()
+            .with_context(|| "Failed to set the number of threads.")?;
+    }
+
+    Ok(())
+}
+
+/// Reads the seed list from the command line or the seed file and selects a random subset if requested.
 ///
-/// From `#` to the end of the line is skipped as a comment.
-/// For each line that is not a blank or comment-only line, read the first value separated by a whitespace character as the seed value.
+/// # Errors
+///
+/// If the seed list is empty, an error is generated.
+fn select_seeds(config: &Config, seed_args: &SeedArguments) -> anyhow::Result<Vec<SeedEntry>> {
+    // Reads the seed list from the command line or a file.
+    let mut seed_entries = match &seed_args.seeds {
+        Some(seed_expression) => seed::annotate_seeds(
+            &seed::parse_seed_expression(seed_expression)?,
+            &config.path.seed_file,
+        )?,
+        None => seed::read_seed_from_file(&config.path.seed_file)?,
+    };
+
+    // Selects a random subset of the seeds.
+    if let Some(sample_num) = seed_args.sample {
+        seed_entries = seed::sample_seeds(&seed_entries, sample_num, seed_args.sample_seed);
+    }
+
+    // Returns an error if the seed list is empty.
+    ensure!(!seed_entries.is_empty(), "Seed list is empty.");
+
+    Ok(seed_entries)
+}
+
+/// Shows the statistics and the failures of the evaluation.
 ///
 /// # Errors
 ///
-/// If the seed cannot be parsed into a non-negative integer, an error is generated.
-fn read_seed_from_file(config: &Config) -> anyhow::Result<Vec<usize>> {
-    let seeds = read_to_string(&config.path.seed_file)
-        .with_context(|| format!("Failed to read seed file `{:?}`.", config.path.seed_file))?
-        .lines()
-        .filter_map(|line| line.split('#').next()?.split_whitespace().next())
-        .map(|seed| {
-            seed.parse::<usize>()
-                .with_context(|| format!("Failed to parse `{}` as seed.", seed))
-        })
-        .collect::<Result<Vec<usize>, _>>()?;
-
-    Ok(seeds)
+/// If there are seeds that failed to be evaluated, an error is generated.
+fn show_report(config: &Config, evaluation_table: &[EvaluationRecord]) -> anyhow::Result<()> {
+    // Shows statistics about scores and execution times.
+    evaluation::show_statistics(&config.statistics, evaluation_table)?;
+
+    // Shows the average progress of the score over time.
+    if let Some(progress) = &config.progress {
+        if evaluation_table
+            .iter()
+            .any(|record| !record.progress.is_empty())
+        {
+            println!();
+            evaluation::show_progress_curve(
+                config.score.objective,
+                progress.point_num,
+                evaluation_table,
+            );
+        }
+    }
+
+    // Shows the seeds that failed to be evaluated.
+    let failure_num = evaluation_table
+        .iter()
+        .filter(|record| !record.is_accepted())
+        .count();
+    if failure_num != 0 {
+        println!();
+        evaluation::show_failures(evaluation_table);
+    }
+
+    // Returns an error if there are seeds that failed to be evaluated.
+    ensure!(
+        failure_num == 0,
+        "Evaluation failed for {} of {} seeds.",
+        failure_num,
+        evaluation_table.len()
+    );
+
+    Ok(())
 }
@@ -0,0 +1,73 @@
+//! Provides a small reproducible pseudo-random number generator.
+
+/// Pseudo-random number generator based on xoshiro256**.
+///
+/// The sequence depends only on the seed, so results are reproducible across platforms and versions.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    /// Creates a generator initialized by the seed.
+    pub fn new(seed: u64) -> Self {
+        // Expands the seed into the state with SplitMix64.
+        let mut x = seed;
+        let mut next_splitmix64 = || {
+            x = x.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            z ^ (z >> 31)
+        };
+
+        Self {
+            state: [
+                next_splitmix64(),
+                next_splitmix64(),
+                next_splitmix64(),
+                next_splitmix64(),
+            ],
+        }
+    }
+
+    /// Returns the next random 64-bit integer.
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+
+        result
+    }
+
+    /// Returns a random floating-point number in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    /// Returns a random integer in `[0, n)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn gen_index(&mut self, n: usize) -> usize {
+        assert!(n != 0, "The range is empty.");
+
+        // Multiply-shift reduction; the bias is negligible for the sizes used here.
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    /// Shuffles the slice in place by the Fisher-Yates algorithm.
+    pub fn shuffle<T>(&mut self, values: &mut [T]) {
+        for i in (1..values.len()).rev() {
+            let j = self.gen_index(i + 1);
+            values.swap(i, j);
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! Provides the parsing and selection of the seeds to be evaluated.
+
+use std::{collections::HashMap, fs::read_to_string, path::Path};
+
+use anyhow::{ensure, Context};
+use itertools::Itertools;
+
+use crate::random::Rng;
+
+/// Seed to be evaluated and the tags and parameters attached to it in the seed file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedEntry {
+    pub seed: usize,
+    pub tags: Vec<String>,
+
+    /// Parameters of the generator written as `NAME=value`, in the order on the line.
+    pub params: Vec<(String, String)>,
+}
+
+/// Parses a seed expression into the list of seeds.
+///
+/// The expression is a comma-separated list of the following items.
+/// - `a`: The single seed `a`.
+/// - `a..b`: The seeds from `a` to `b` excluding `b`.
+/// - `a..=b` or `a-b`: The seeds from `a` to `b` including `b`.
+///
+/// Seeds that appear more than once are listed only at their first occurrence.
+///
+/// # Errors
+///
+/// If an item cannot be parsed, an error is generated.
+pub fn parse_seed_expression(expression: &str) -> anyhow::Result<Vec<usize>> {
+    let mut seeds = vec![];
+
+    for item in expression.split(',').map(str::trim) {
+        ensure!(
+            !item.is_empty(),
+            "Empty item in seed expression `{}`.",
+            expression
+        );
+
+        // Returns the seed following the last seed of the item, which must not overflow.
+        let next_seed = |seed: usize| {
+            seed.checked_add(1)
+                .with_context(|| format!("Seed range `{}` is too large.", item))
+        };
+
+        let (start, end) = if let Some((start, end)) = item.split_once("..=") {
+            (parse_seed(start)?, next_seed(parse_seed(end)?)?)
+        } else if let Some((start, end)) = item.split_once("..") {
+            (parse_seed(start)?, parse_seed(end)?)
+        } else if let Some((start, end)) = item.split_once('-') {
+            (parse_seed(start)?, next_seed(parse_seed(end)?)?)
+        } else {
+            let seed = parse_seed(item)?;
+            (seed, next_seed(seed)?)
+        };
+
+        ensure!(start < end, "Seed range `{}` is empty.", item);
+
+        seeds.extend(start..end);
+    }
+
+    Ok(seeds.into_iter().unique().collect())
+}
+
+/// Reads the seed list from a file.
+///
+/// From `#` to the end of the line is skipped as a comment.
+/// For each line that is not a blank or comment-only line,
+/// the first value separated by a whitespace character is read as a seed expression (see `parse_seed_expression`),
+/// and the remaining values are read as the parameters of the generator if they are written as `NAME=value`,
+/// and as the tags of the seeds otherwise.
+/// If a seed appears more than once, only its first occurrence is used.
+///
+/// # Errors
+///
+/// If a seed expression cannot be parsed, an error is generated.
+pub fn read_seed_from_file<P>(seed_file_path: P) -> anyhow::Result<Vec<SeedEntry>>
+where
+    P: AsRef<Path>,
+{
+    let seed_file_path = seed_file_path.as_ref();
+
+    let mut seed_entries = vec![];
+
+    for line in read_to_string(seed_file_path)
+        .with_context(|| format!("Failed to read seed file `{:?}`.", seed_file_path))?
+        .lines()
+    {
+        let mut values = line.split('#').next().unwrap().split_whitespace();
+
+        let Some(seed_expression) = values.next() else {
+            continue;
+        };
+        let mut tags = vec![];
+        let mut params = vec![];
+        for value in values {
+            match value.split_once('=') {
+                Some((name, param)) => params.push((name.to_owned(), param.to_owned())),
+                None => tags.push(value.to_owned()),
+            }
+        }
+
+        for seed in parse_seed_expression(seed_expression)? {
+            seed_entries.push(SeedEntry {
+                seed,
+                tags: tags.clone(),
+                params: params.clone(),
+            });
+        }
+    }
+
+    Ok(seed_entries
+        .into_iter()
+        .unique_by(|seed_entry| seed_entry.seed)
+        .collect())
+}
+
+/// Attaches the tags and parameters written in the seed file to the seeds.
+///
+/// If the seed file does not exist, no tags or parameters are attached.
+pub fn annotate_seeds<P>(seeds: &[usize], seed_file_path: P) -> anyhow::Result<Vec<SeedEntry>>
+where
+    P: AsRef<Path>,
+{
+    let seed_to_entry: HashMap<usize, SeedEntry> = if seed_file_path.as_ref().exists() {
+        read_seed_from_file(seed_file_path)?
+            .into_iter()
+            .map(|seed_entry| (seed_entry.seed, seed_entry))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
+    let seed_entries = seeds
+        .iter()
+        .map(|&seed| match seed_to_entry.get(&seed) {
+            Some(seed_entry) => seed_entry.clone(),
+            None => SeedEntry {
+                seed,
+                tags: vec![],
+                params: vec![],
+            },
+        })
+        .collect();
+
+    Ok(seed_entries)
+}
+
+/// Selects `sample_num` seeds at random from the seed list.
+///
+/// The selection depends only on `sample_seed`, and the selected seeds keep their order in the list.
+/// If `sample_num` is not less than the number of seeds, all seeds are selected.
+pub fn sample_seeds(
+    seed_entries: &[SeedEntry],
+    sample_num: usize,
+    sample_seed: u64,
+) -> Vec<SeedEntry> {
+    let mut rng = Rng::new(sample_seed);
+
+    let mut indices = (0..seed_entries.len()).collect_vec();
+    rng.shuffle(&mut indices);
+
+    indices
+        .into_iter()
+        .take(sample_num)
+        .sorted()
+        .map(|index| seed_entries[index].clone())
+        .collect()
+}
+
+/// Parses a single seed.
+fn parse_seed(seed: &str) -> anyhow::Result<usize> {
+    seed.trim()
+        .parse::<usize>()
+        .with_context(|| format!("Failed to parse `{}` as seed.", seed))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    #[test]
+    fn parse_single_seeds_and_ranges() {
+        assert_eq!(parse_seed_expression("3").unwrap(), vec![3]);
+        assert_eq!(parse_seed_expression("0..3").unwrap(), vec![0, 1, 2]);
+        assert_eq!(parse_seed_expression("0..=3").unwrap(), vec![0, 1, 2, 3]);
+        assert_eq!(parse_seed_expression("2-4").unwrap(), vec![2, 3, 4]);
+        assert_eq!(
+            parse_seed_expression(" 5 , 0..2 ,7-8").unwrap(),
+            vec![5, 0, 1, 7, 8]
+        );
+    }
+
+    #[test]
+    fn remove_duplicate_seeds_keeping_first_occurrence() {
+        assert_eq!(
+            parse_seed_expression("3,0..5,1,4").unwrap(),
+            vec![3, 0, 1, 2, 4]
+        );
+    }
+
+    #[test]
+    fn reject_invalid_expressions() {
+        for expression in ["", "1,,2", "a", "3..3", "5-4", "-1", "1..=x"] {
+            assert!(
+                parse_seed_expression(expression).is_err(),
+                "`{}` should be rejected.",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn reject_ranges_that_overflow() {
+        let max = usize::MAX;
+
+        assert!(parse_seed_expression(&max.to_string()).is_err());
+        assert!(parse_seed_expression(&format!("0..={}", max)).is_err());
+        assert!(parse_seed_expression(&format!("{}-{}", max, max)).is_err());
+        assert_eq!(
+            parse_seed_expression(&format!("{}..{}", max - 1, max)).unwrap(),
+            vec![max - 1]
+        );
+    }
+
+    #[test]
+    fn read_tags_and_params_from_file() {
+        let seed_file_path =
+            env::temp_dir().join(format!("ahc-evaluation-seeds-{}.txt", process::id()));
+        fs::write(
+            &seed_file_path,
+            "# comment\n0..2 small N=10\n\n5 hard # note\n1 large\n",
+        )
+        .unwrap();
+
+        let seed_entries = read_seed_from_file(&seed_file_path);
+        fs::remove_file(&seed_file_path).unwrap();
+
+        let entry = |seed: usize, tags: &[&str], params: &[(&str, &str)]| SeedEntry {
+            seed,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            params: params
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        };
+        assert_eq!(
+            seed_entries.unwrap(),
+            vec![
+                entry(0, &["small"], &[("N", "10")]),
+                entry(1, &["small"], &[("N", "10")]),
+                entry(5, &["hard"], &[]),
+            ]
+        );
+    }
+
+    #[test]
+    fn sample_seeds_reproducibly_in_order() {
+        let seed_entries = parse_seed_expression("0..20")
+            .unwrap()
+            .into_iter()
+            .map(|seed| SeedEntry {
+                seed,
+                tags: vec![],
+                params: vec![],
+            })
+            .collect_vec();
+
+        let sampled = sample_seeds(&seed_entries, 5, 42);
+        assert_eq!(sampled.len(), 5);
+        assert!(sampled.windows(2).all(|pair| pair[0].seed < pair[1].seed));
+        assert_eq!(sampled, sample_seeds(&seed_entries, 5, 42));
+
+        assert_eq!(sample_seeds(&seed_entries, 100, 42), seed_entries);
+    }
+}
@@ -0,0 +1,480 @@
+//! Provides descriptive statistics and statistical tests for paired samples.
+
+use itertools::Itertools;
+
+use crate::random::Rng;
+
+/// Result of a statistical test.
+#[derive(Debug, Clone, Copy)]
+pub struct TestResult {
+    /// Test statistic.
+    pub statistic: f64,
+
+    /// Two-sided p-value.
+    pub p_value: f64,
+}
+
+/// Returns the arithmetic mean of the values.
+pub fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Returns the unbiased sample variance of the values.
+pub fn variance(values: &[f64]) -> f64 {
+    let mean = mean(values);
+
+    values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / (values.len() - 1) as f64
+}
+
+/// Returns the sample standard deviation of the values.
+///
+/// Returns 0 if there are fewer than two values.
+pub fn std_dev(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+
+    variance(values).sqrt()
+}
+
+/// Returns a one-line histogram of the values drawn with block characters.
+pub fn sparkline(values: &[f64], bin_num: usize) -> String {
+    /// Characters representing the heights of the bins.
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let (min, max) = values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+            (min.min(value), max.max(value))
+        });
+
+    let mut counts = vec![0_usize; bin_num];
+    for &value in values {
+        let bin = if max > min {
+            (((value - min) / (max - min) * bin_num as f64) as usize).min(bin_num - 1)
+        } else {
+            0
+        };
+        counts[bin] += 1;
+    }
+
+    let max_count = counts.iter().copied().max().unwrap_or_default();
+    counts
+        .iter()
+        .map(|&count| {
+            if count == 0 {
+                ' '
+            } else {
+                BLOCKS[(count * BLOCKS.len()).div_ceil(max_count) - 1]
+            }
+        })
+        .collect()
+}
+
+/// Performs the paired t-test of whether the mean of the differences is zero.
+///
+/// Returns `None` if there are fewer than two differences.
+pub fn paired_t_test(diffs: &[f64]) -> Option<TestResult> {
+    if diffs.len() < 2 {
+        return None;
+    }
+
+    let mean = mean(diffs);
+    let std_error = (variance(diffs) / diffs.len() as f64).sqrt();
+
+    if std_error == 0.0 {
+        let p_value = if mean == 0.0 { 1.0 } else { 0.0 };
+        let statistic = if mean == 0.0 {
+            0.0
+        } else {
+            mean.signum() * f64::INFINITY
+        };
+
+        return Some(TestResult { statistic, p_value });
+    }
+
+    let statistic = mean / std_error;
+    let df = (diffs.len() - 1) as f64;
+    let p_value = regularized_incomplete_beta(df / (df + statistic * statistic), df / 2.0, 0.5);
+
+    Some(TestResult { statistic, p_value })
+}
+
+/// Performs the Wilcoxon signed-rank test of whether the differences are symmetric about zero.
+///
+/// Zero differences are excluded, and the p-value is calculated by the normal approximation
+/// with tie and continuity corrections.
+/// The statistic is the z-score of the sum of the ranks of the positive differences.
+/// Returns `None` if all differences are zero.
+pub fn wilcoxon_signed_rank_test(diffs: &[f64]) -> Option<TestResult> {
+    let nonzero_diffs = diffs
+        .iter()
+        .copied()
+        .filter(|&diff| diff != 0.0)
+        .sorted_by(|x, y| x.abs().total_cmp(&y.abs()))
+        .collect_vec();
+
+    if nonzero_diffs.is_empty() {
+        return None;
+    }
+
+    let n = nonzero_diffs.len() as f64;
+
+    // Assigns average ranks to ties of the absolute values.
+    let mut positive_rank_sum = 0.0;
+    let mut tie_correction = 0.0;
+    let mut start = 0;
+    while start < nonzero_diffs.len() {
+        let end = start
+            + nonzero_diffs[start..]
+                .iter()
+                .take_while(|diff| diff.abs() == nonzero_diffs[start].abs())
+                .count();
+
+        let tie_num = (end - start) as f64;
+        let average_rank = (start + 1 + end) as f64 / 2.0;
+        let positive_num = nonzero_diffs[start..end]
+            .iter()
+            .filter(|&&diff| diff > 0.0)
+            .count();
+
+        positive_rank_sum += average_rank * positive_num as f64;
+        tie_correction += tie_num.powi(3) - tie_num;
+
+        start = end;
+    }
+
+    let expected = n * (n + 1.0) / 4.0;
+    let variance = n * (n + 1.0) * (2.0 * n + 1.0) / 24.0 - tie_correction / 48.0;
+
+    if variance == 0.0 {
+        return Some(TestResult {
+            statistic: 0.0,
+            p_value: 1.0,
+        });
+    }
+
+    let deviation = positive_rank_sum - expected;
+    let corrected_deviation = (deviation.abs() - 0.5).max(0.0) * deviation.signum();
+    let statistic = corrected_deviation / variance.sqrt();
+
+    Some(TestResult {
+        statistic,
+        p_value: normal_two_sided_p_value(statistic),
+    })
+}
+
+/// Performs the exact sign test of whether positive and negative differences are equally likely.
+///
+/// Zero differences are excluded.
+/// The statistic is the number of positive differences.
+/// Returns `None` if all differences are zero.
+pub fn sign_test(diffs: &[f64]) -> Option<TestResult> {
+    let positive_num = diffs.iter().filter(|&&diff| diff > 0.0).count();
+    let negative_num = diffs.iter().filter(|&&diff| diff < 0.0).count();
+    let n = positive_num + negative_num;
+
+    if n == 0 {
+        return None;
+    }
+
+    // Sums the probabilities of the binomial distribution in the tail.
+    let tail_probability = (0..=positive_num.min(negative_num))
+        .map(|k| (ln_binomial(n, k) - n as f64 * std::f64::consts::LN_2).exp())
+        .sum::<f64>();
+
+    Some(TestResult {
+        statistic: positive_num as f64,
+        p_value: (2.0 * tail_probability).min(1.0),
+    })
+}
+
+/// Returns the percentile bootstrap confidence interval of the mean of the values.
+///
+/// The resampling is reproducible because the random number generator is initialized with a fixed seed.
+pub fn bootstrap_mean_confidence_interval(
+    values: &[f64],
+    confidence_level: f64,
+    iteration_num: usize,
+) -> (f64, f64) {
+    /// Seed of the random number generator used for resampling.
+    const RESAMPLING_SEED: u64 = 0;
+
+    let mut rng = Rng::new(RESAMPLING_SEED);
+
+    let means = (0..iteration_num)
+        .map(|_| {
+            let sum = (0..values.len())
+                .map(|_| values[rng.gen_index(values.len())])
+                .sum::<f64>();
+
+            sum / values.len() as f64
+        })
+        .sorted_by(|x, y| x.total_cmp(y))
+        .collect_vec();
+
+    let alpha = 1.0 - confidence_level;
+    let lower = quantile_of_sorted(&means, alpha / 2.0);
+    let upper = quantile_of_sorted(&means, 1.0 - alpha / 2.0);
+
+    (lower, upper)
+}
+
+/// Returns the quantile of the sorted values by linear interpolation.
+///
+/// `q` must be in `[0, 1]`.
+pub fn quantile_of_sorted(sorted_values: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted_values.len() - 1) as f64;
+    let lower = pos.floor() as usize;
+    let upper = pos.ceil() as usize;
+
+    sorted_values[lower] + (sorted_values[upper] - sorted_values[lower]) * (pos - lower as f64)
+}
+
+/// Returns the two-sided p-value of the z-score under the standard normal distribution.
+fn normal_two_sided_p_value(z: f64) -> f64 {
+    erfc(z.abs() / std::f64::consts::SQRT_2)
+}
+
+/// Returns the complementary error function.
+///
+/// The approximation has a fractional error of less than 1.2e-7.
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let r = t
+        * (-z * z - 1.26551223
+            + t * (1.00002368
+                + t * (0.37409196
+                    + t * (0.09678418
+                        + t * (-0.18628806
+                            + t * (0.27886807
+                                + t * (-1.13520398
+                                    + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277)))))))))
+            .exp();
+
+    if x >= 0.0 {
+        r
+    } else {
+        2.0 - r
+    }
+}
+
+/// Returns the natural logarithm of the binomial coefficient.
+fn ln_binomial(n: usize, k: usize) -> f64 {
+    ln_gamma(n as f64 + 1.0) - ln_gamma(k as f64 + 1.0) - ln_gamma((n - k) as f64 + 1.0)
+}
+
+/// Returns the natural logarithm of the gamma function by the Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        // Reflection formula.
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let a = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |acc, (i, &c)| {
+            acc + c / (x + (i + 1) as f64)
+        });
+    let t = x + 7.5;
+
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + a.ln()
+}
+
+/// Returns the regularized incomplete beta function `I_x(a, b)`.
+fn regularized_incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+
+    // The continued fraction converges rapidly for `x < (a + 1) / (a + b + 2)`.
+    if x < (a + 1.0) / (a + b + 2.0) {
+        ln_front.exp() * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - ln_front.exp() * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+/// Evaluates the continued fraction for the incomplete beta function by the modified Lentz's method.
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const MAX_ITERATION_NUM: usize = 300;
+    const EPSILON: f64 = 1e-15;
+    const FLOOR: f64 = 1e-300;
+
+    let clamp = |value: f64| {
+        if value.abs() < FLOOR {
+            FLOOR
+        } else {
+            value
+        }
+    };
+
+    let mut c = 1.0;
+    let mut d = 1.0 / clamp(1.0 - (a + b) * x / (a + 1.0));
+    let mut h = d;
+
+    for m in 1..=MAX_ITERATION_NUM {
+        let m = m as f64;
+
+        // Even step.
+        let numerator = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / clamp(1.0 + numerator * d);
+        c = clamp(1.0 + numerator / c);
+        h *= d * c;
+
+        // Odd step.
+        let numerator = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / clamp(1.0 + numerator * d);
+        c = clamp(1.0 + numerator / c);
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts that the values are equal within the tolerance.
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{} is not close to {}.",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn mean_and_variance() {
+        let values = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+
+        assert_close(mean(&values), 5.0, 1e-12);
+        assert_close(variance(&values), 32.0 / 7.0, 1e-12);
+    }
+
+    #[test]
+    fn std_dev_of_few_values() {
+        assert_eq!(std_dev(&[]), 0.0);
+        assert_eq!(std_dev(&[3.0]), 0.0);
+        assert_close(std_dev(&[1.0, 3.0]), 2.0_f64.sqrt(), 1e-12);
+    }
+
+    #[test]
+    fn draw_sparkline() {
+        assert_eq!(sparkline(&[1.0, 1.0, 1.0, 2.0, 3.0, 3.0], 3), "█▃▆");
+        assert_eq!(sparkline(&[0.0, 0.0, 10.0], 4), "█  ▄");
+
+        // All values fall into the first bin if they are equal.
+        assert_eq!(sparkline(&[5.0, 5.0], 3), "█  ");
+    }
+
+    #[test]
+    fn interpolate_quantiles() {
+        let sorted_values = [1.0, 2.0, 3.0, 4.0, 5.0];
+
+        assert_close(quantile_of_sorted(&sorted_values, 0.0), 1.0, 1e-12);
+        assert_close(quantile_of_sorted(&sorted_values, 0.5), 3.0, 1e-12);
+        assert_close(quantile_of_sorted(&sorted_values, 0.875), 4.5, 1e-12);
+        assert_close(quantile_of_sorted(&sorted_values, 1.0), 5.0, 1e-12);
+    }
+
+    #[test]
+    fn paired_t_test_p_values() {
+        let result = paired_t_test(&[1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+        assert_close(result.statistic, 4.242641, 1e-6);
+        assert_close(result.p_value, 0.013236, 1e-5);
+
+        let result = paired_t_test(&[0.5, -1.0, 2.0, 1.5, 3.0, -0.5]).unwrap();
+        assert_close(result.statistic, 1.467319, 1e-6);
+        assert_close(result.p_value, 0.202209, 1e-5);
+
+        // The p-value does not depend on the sign of the differences.
+        let result = paired_t_test(&[-1.0, -2.0, -3.0, -4.0, -5.0]).unwrap();
+        assert_close(result.statistic, -4.242641, 1e-6);
+        assert_close(result.p_value, 0.013236, 1e-5);
+    }
+
+    #[test]
+    fn paired_t_test_degenerate_cases() {
+        assert!(paired_t_test(&[1.0]).is_none());
+
+        let result = paired_t_test(&[0.0, 0.0, 0.0]).unwrap();
+        assert_eq!(result.p_value, 1.0);
+
+        let result = paired_t_test(&[2.0, 2.0, 2.0]).unwrap();
+        assert_eq!(result.statistic, f64::INFINITY);
+        assert_eq!(result.p_value, 0.0);
+    }
+
+    #[test]
+    fn wilcoxon_signed_rank_test_p_values() {
+        let result = wilcoxon_signed_rank_test(&[1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+        assert_close(result.statistic, 1.887760, 1e-6);
+        assert_close(result.p_value, 0.059058, 1e-5);
+
+        // Ties share the average rank, and zero differences are excluded.
+        let result = wilcoxon_signed_rank_test(&[1.0, -1.0, 0.0, 2.0, 2.0, 3.0]).unwrap();
+        assert_close(result.statistic, 1.496910, 1e-6);
+        assert_close(result.p_value, 0.134417, 1e-5);
+
+        assert!(wilcoxon_signed_rank_test(&[0.0, 0.0]).is_none());
+    }
+
+    #[test]
+    fn sign_test_p_values() {
+        let diffs = [1.0, 2.0, 3.0, 1.0, 1.0, 2.0, 5.0, 1.0, -1.0, -2.0, 0.0];
+
+        let result = sign_test(&diffs).unwrap();
+        assert_eq!(result.statistic, 8.0);
+        assert_close(result.p_value, 56.0 / 512.0, 1e-9);
+
+        let result = sign_test(&[1.0, -1.0]).unwrap();
+        assert_close(result.p_value, 1.0, 1e-9);
+
+        assert!(sign_test(&[0.0]).is_none());
+    }
+
+    #[test]
+    fn bootstrap_interval_contains_mean() {
+        let values = (0..50).map(|i| i as f64).collect_vec();
+
+        let (lower, upper) = bootstrap_mean_confidence_interval(&values, 0.95, 1000);
+        assert!(lower < 24.5 && 24.5 < upper);
+        assert_eq!(
+            (lower, upper),
+            bootstrap_mean_confidence_interval(&values, 0.95, 1000)
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Provides the combinations of the parameter values evaluated by `sweep`.
+
+use anyhow::Context;
+use itertools::Itertools;
+
+use crate::config::ParamConfig;
+
+/// Returns every combination of the candidate values of the parameters.
+///
+/// Each combination is the list of the names and values of the parameters in the order of declaration.
+///
+/// # Errors
+///
+/// If a parameter has neither `values` nor `step`, an error is generated.
+pub fn combinations(params: &[ParamConfig]) -> anyhow::Result<Vec<Vec<(String, String)>>> {
+    let mut param_values = vec![];
+
+    for param in params {
+        let values = param.grid_values().with_context(|| {
+            format!(
+                "Parameter `{}` must have `values` or `step` to be swept.",
+                param.name
+            )
+        })?;
+
+        param_values.push(
+            values
+                .into_iter()
+                .map(|value| (param.name.clone(), value))
+                .collect_vec(),
+        );
+    }
+
+    Ok(param_values.into_iter().multi_cartesian_product().collect())
+}
+
+/// Formats the combination as `NAME=value` separated by spaces.
+pub fn format_combination(param_values: &[(String, String)]) -> String {
+    param_values
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .join(" ")
+}
+
+/// Returns the name of the directory for the combination, which is `NAME=value` separated by commas.
+///
+/// Slashes in the values are replaced with underscores so that the name is a single path component.
+pub fn combination_dir_name(param_values: &[(String, String)]) -> String {
+    param_values
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value.replace('/', "_")))
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn param(toml: &str) -> ParamConfig {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn grid_values_of_each_kind() {
+        let values = param("name = \"A\"\nvalues = [1, 2.5, \"x\"]");
+        assert_eq!(
+            values.grid_values(),
+            Some(vec!["1".to_owned(), "2.5".to_owned(), "x".to_owned()])
+        );
+
+        let integers = param("name = \"B\"\nmin = 10\nmax = 20\nstep = 4");
+        assert_eq!(
+            integers.grid_values(),
+            Some(vec!["10".to_owned(), "14".to_owned(), "18".to_owned()])
+        );
+
+        // `max` is kept against rounding errors of the step.
+        let floats = param("name = \"C\"\nmin = 0.0\nmax = 0.3\nstep = 0.1");
+        assert_eq!(
+            floats.grid_values(),
+            Some(vec![
+                "0".to_owned(),
+                "0.1".to_owned(),
+                "0.2".to_owned(),
+                "0.3".to_owned()
+            ])
+        );
+
+        let without_step = param("name = \"D\"\nmin = 0.0\nmax = 1.0");
+        assert_eq!(without_step.grid_values(), None);
+    }
+
+    #[test]
+    fn enumerate_combinations_in_order() {
+        let params = [
+            param("name = \"A\"\nvalues = [1, 2]"),
+            param("name = \"B\"\nmin = 0\nmax = 2\nstep = 1"),
+        ];
+        let combinations = combinations(&params).unwrap();
+
+        let labels = combinations
+            .iter()
+            .map(|param_values| format_combination(param_values))
+            .collect_vec();
+        assert_eq!(
+            labels,
+            ["A=1 B=0", "A=1 B=1", "A=1 B=2", "A=2 B=0", "A=2 B=1", "A=2 B=2"]
+        );
+    }
+
+    #[test]
+    fn reject_range_without_step() {
+        let params = [
+            param("name = \"A\"\nvalues = [1, 2]"),
+            param("name = \"B\"\nmin = 0.0\nmax = 1.0"),
+        ];
+
+        assert!(combinations(&params).is_err());
+    }
+
+    #[test]
+    fn name_directory_of_combination() {
+        let param_values = [
+            ("A".to_owned(), "1".to_owned()),
+            ("B".to_owned(), "a/b".to_owned()),
+        ];
+
+        assert_eq!(combination_dir_name(&param_values), "A=1,B=a_b");
+    }
+}
@@ -0,0 +1,166 @@
+//! Provides the expansion of placeholders such as `{seed}` or `{seed:04}` in templates.
+
+use anyhow::{bail, ensure, Context};
+
+/// Expands the placeholders in the template.
+///
+/// A placeholder is written as `{name}` or `{name:width}`.
+/// The value is padded on the left to `width` characters, with zeros if `width` starts with `0` (e.g. `{seed:04}`)
+/// and with spaces otherwise.
+/// `{{` and `}}` are written as `{` and `}`.
+/// `lookup` returns the value of the placeholder with the name, or `None` if the name is unknown.
+///
+/// # Errors
+///
+/// If the template is malformed or contains an unknown placeholder, an error is generated.
+pub fn expand<F>(template: &str, lookup: F) -> anyhow::Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut expanded = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                expanded.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                expanded.push('}');
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        closed = true;
+                        break;
+                    }
+                    placeholder.push(c);
+                }
+
+                ensure!(closed, "Unclosed `{{` in `{}`.", template);
+
+                let (name, width) = match placeholder.split_once(':') {
+                    Some((name, width)) => (name, Some(width)),
+                    None => (placeholder.as_str(), None),
+                };
+
+                let Some(value) = lookup(name) else {
+                    bail!(
+                        "Unknown placeholder `{{{}}}` in `{}`.",
+                        placeholder,
+                        template
+                    );
+                };
+
+                match width {
+                    Some(width) => {
+                        let zero_padding = width.starts_with('0');
+                        let width = width.parse::<usize>().with_context(|| {
+                            format!("Invalid width `{}` in `{}`.", width, template)
+                        })?;
+
+                        let padding = if zero_padding { '0' } else { ' ' };
+                        let padding_len = width.saturating_sub(value.chars().count());
+                        expanded.extend(std::iter::repeat_n(padding, padding_len));
+                        expanded.push_str(&value);
+                    }
+                    None => expanded.push_str(&value),
+                }
+            }
+            '}' => bail!("Unmatched `}}` in `{}`.", template),
+            c => expanded.push(c),
+        }
+    }
+
+    Ok(expanded)
+}
+
+/// Checks that the template contains only the placeholders with the names.
+pub fn validate(template: &str, names: &[&str]) -> anyhow::Result<()> {
+    expand(template, |name| names.contains(&name).then(String::new))?;
+
+    Ok(())
+}
+
+/// Quotes the argument with single quotes if it contains characters that have special meanings in the shell.
+pub fn shell_quote(arg: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c);
+
+    if !arg.is_empty() && arg.chars().all(is_safe) {
+        arg.to_owned()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "seed" => Some("42".to_owned()),
+            "input" => Some("in/0042.txt".to_owned()),
+            "empty" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expand_placeholders_anywhere() {
+        assert_eq!(expand("{seed}", lookup).unwrap(), "42");
+        assert_eq!(
+            expand("--in={input} --seed={seed}", lookup).unwrap(),
+            "--in=in/0042.txt --seed=42"
+        );
+        assert_eq!(expand("a{empty}b", lookup).unwrap(), "ab");
+        assert_eq!(
+            expand("no placeholders", lookup).unwrap(),
+            "no placeholders"
+        );
+    }
+
+    #[test]
+    fn pad_values_to_width() {
+        assert_eq!(expand("{seed:04}", lookup).unwrap(), "0042");
+        assert_eq!(expand("{seed:4}", lookup).unwrap(), "  42");
+        assert_eq!(expand("{seed:1}", lookup).unwrap(), "42");
+    }
+
+    #[test]
+    fn unescape_braces() {
+        assert_eq!(expand("{{seed}}", lookup).unwrap(), "{seed}");
+        assert_eq!(expand("{{{seed}}}", lookup).unwrap(), "{42}");
+    }
+
+    #[test]
+    fn reject_malformed_templates() {
+        for template in ["{seed", "seed}", "{unknown}", "{seed:x}", "{}"] {
+            assert!(
+                expand(template, lookup).is_err(),
+                "`{}` should be rejected.",
+                template
+            );
+        }
+    }
+
+    #[test]
+    fn validate_placeholder_names() {
+        assert!(validate("{seed:04}.txt", &["seed"]).is_ok());
+        assert!(validate("{input}", &["seed"]).is_err());
+    }
+
+    #[test]
+    fn quote_only_unsafe_arguments() {
+        assert_eq!(shell_quote("./a.out"), "./a.out");
+        assert_eq!(shell_quote("--seed=42"), "--seed=42");
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("my dir/a.out"), "'my dir/a.out'");
+        assert_eq!(shell_quote("$HOME"), "'$HOME'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+}
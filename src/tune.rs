@@ -0,0 +1,991 @@
+//! Provides the search for the best values of the parameters by random search, successive halving
+//! and the Tree-structured Parzen Estimator (TPE).
+//!
+//! Every evaluation is appended to the trial file, and evaluations of the same values on the same seeds are reused
+//! as long as the commands, the score retrieval and the commit of the git repository are unchanged.
+//! Since the search is deterministic for `tune.seed`, executing it again replays the saved trials and resumes the search.
+
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    f64::consts::PI,
+    fs::{create_dir_all, read_to_string, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+use anyhow::Context;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    config::{Config, Objective, ParamConfig, TuneMethod},
+    evaluation::{self, is_better, relative_score, BestScoreTable, EvaluationRecord, Status},
+    history,
+    random::Rng,
+    seed::SeedEntry,
+    statistics, sweep,
+};
+
+/// Fraction of the trials regarded as good by TPE.
+const TPE_GAMMA: f64 = 0.25;
+
+/// Number of candidates sampled from the estimator of the good trials in each step of TPE.
+const TPE_CANDIDATE_NUM: usize = 24;
+
+/// Number of trials shown in the ranking.
+const RANKING_TRIAL_NUM: usize = 10;
+
+/// Values of the parameters and their evaluation records.
+type Trial = (Vec<(String, String)>, Vec<EvaluationRecord>);
+
+/// Evaluation of the values of the parameters on some seeds, saved as a line of the trial file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TrialEntry {
+    /// Fingerprint of the programs with which the evaluation was executed.
+    #[serde(default)]
+    fingerprint: String,
+    params: Vec<(String, String)>,
+    records: Vec<EvaluationRecord>,
+}
+
+/// Evaluation records for each value of the parameters and seed, backed by the trial file.
+struct TrialStore {
+    trial_file_path: PathBuf,
+    fingerprint: String,
+    records: HashMap<(Vec<(String, String)>, usize), EvaluationRecord>,
+
+    /// Best known score for each seed among the best score file and the evaluations,
+    /// or `None` if the best score file is not specified.
+    best_scores: Option<BestScoreTable>,
+
+    /// Number of evaluations in the trial file ignored because of a different fingerprint.
+    stale_num: usize,
+}
+
+impl TrialStore {
+    /// Reads the evaluations saved in the trial file if it exists.
+    ///
+    /// The evaluations saved with a fingerprint different from the current one are ignored.
+    fn open(config: &Config) -> anyhow::Result<Self> {
+        let trial_file_path = &config.tune.trial_file;
+        let fingerprint = fingerprint(config);
+        let mut records = HashMap::new();
+        let mut stale_num = 0;
+
+        if trial_file_path.exists() {
+            let text = read_to_string(trial_file_path)
+                .with_context(|| format!("Failed to read trial file `{:?}`.", trial_file_path))?;
+
+            for (i, line) in text.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let entry: TrialEntry = serde_json::from_str(line).with_context(|| {
+                    format!(
+                        "Failed to parse line {} of trial file `{:?}`.",
+                        i + 1,
+                        trial_file_path
+                    )
+                })?;
+
+                if entry.fingerprint != fingerprint {
+                    stale_num += entry.records.len();
+                    continue;
+                }
+
+                for record in entry.records {
+                    records.insert((entry.params.clone(), record.seed), record);
+                }
+            }
+        }
+
+        let best_scores = match &config.path.best_score {
+            Some(best_score_file_path) => {
+                let mut best_scores = BestScoreTable::read_from_file(best_score_file_path)?;
+                for record in records.values() {
+                    update_best_score(&mut best_scores, record, config.score.objective);
+                }
+
+                Some(best_scores)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            trial_file_path: trial_file_path.to_owned(),
+            fingerprint,
+            records,
+            best_scores,
+            stale_num,
+        })
+    }
+
+    /// Returns the number of saved evaluations of the seeds.
+    fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Evaluates the submission code with the values of the parameters on the seeds and returns the records.
+    ///
+    /// Only the seeds that have not been evaluated with the values are evaluated, and their records are saved
+    /// except for internal errors, which do not depend on the values.
+    fn evaluate(
+        &mut self,
+        config: &Config,
+        params: &[(String, String)],
+        seed_entries: &[SeedEntry],
+    ) -> anyhow::Result<Vec<EvaluationRecord>> {
+        let missing_entries = seed_entries
+            .iter()
+            .filter(|seed_entry| {
+                !self
+                    .records
+                    .contains_key(&(params.to_vec(), seed_entry.seed))
+            })
+            .unique_by(|seed_entry| seed_entry.seed)
+            .cloned()
+            .collect_vec();
+
+        if !missing_entries.is_empty() {
+            // Failed seeds are recorded instead of stopping the search.
+            let mut config = config.clone();
+            config.param_values = params.to_vec();
+            config.command.execute.keep_going = true;
+
+            let records = evaluation::evaluate(&config, &missing_entries)?;
+
+            let entry = TrialEntry {
+                fingerprint: self.fingerprint.clone(),
+                params: params.to_vec(),
+                records: records
+                    .iter()
+                    .filter(|record| record.status != Status::InternalError)
+                    .cloned()
+                    .collect(),
+            };
+            if !entry.records.is_empty() {
+                self.append(&entry)?;
+            }
+
+            for record in records {
+                if let Some(best_scores) = &mut self.best_scores {
+                    update_best_score(best_scores, &record, config.score.objective);
+                }
+                self.records.insert((params.to_vec(), record.seed), record);
+            }
+        }
+
+        Ok(seed_entries
+            .iter()
+            .map(|seed_entry| self.records[&(params.to_vec(), seed_entry.seed)].clone())
+            .collect())
+    }
+
+    /// Appends the evaluation to the trial file.
+    fn append(&self, entry: &TrialEntry) -> anyhow::Result<()> {
+        if let Some(trial_dir) = self.trial_file_path.parent() {
+            create_dir_all(trial_dir)
+                .with_context(|| format!("Failed to create the directory `{:?}`.", trial_dir))?;
+        }
+
+        let line = serde_json::to_string(entry).with_context(|| "Failed to serialize trial.")?;
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.trial_file_path)
+            .and_then(|mut file| writeln!(file, "{}", line))
+            .with_context(|| {
+                format!(
+                    "Failed to write to trial file `{:?}`.",
+                    self.trial_file_path
+                )
+            })
+    }
+}
+
+/// Searches for the best values of the parameters and shows the ranking of the trials and the statistics of the best.
+///
+/// # Errors
+///
+/// If an evaluation fails to be executed or saved, an error is generated.
+pub fn tune(config: &Config, seed_entries: &[SeedEntry]) -> anyhow::Result<()> {
+    let tune_config = &config.tune;
+
+    let mut trial_store = TrialStore::open(config)?;
+    if trial_store.stale_num != 0 {
+        println!(
+            "Ignoring {} evaluations saved in `{}` with other commands or another commit.",
+            trial_store.stale_num,
+            tune_config.trial_file.display()
+        );
+    }
+    if trial_store.len() != 0 {
+        println!(
+            "Resuming from {} evaluations saved in `{}`.",
+            trial_store.len(),
+            tune_config.trial_file.display()
+        );
+    }
+
+    let trials = match tune_config.method {
+        TuneMethod::Random => random_search(config, seed_entries, &mut trial_store)?,
+        TuneMethod::SuccessiveHalving => {
+            successive_halving(config, seed_entries, &mut trial_store)?
+        }
+        TuneMethod::Tpe => tpe(config, seed_entries, &mut trial_store)?,
+    };
+
+    // Ranks the trials evaluated on all seeds.
+    let objective = config.score.objective;
+    let best_scores = trial_store.best_scores.as_ref();
+    let ranking = trials
+        .iter()
+        .filter(|(_, records)| records.len() == seed_entries.len())
+        .unique_by(|(params, _)| params.clone())
+        .sorted_by(|(_, records_a), (_, records_b)| {
+            compare_trials(records_a, records_b, objective, best_scores)
+        })
+        .collect_vec();
+
+    println!();
+    show_ranking(&ranking, objective, best_scores);
+
+    let (best_params, best_records) = ranking
+        .first()
+        .with_context(|| "No trial was evaluated on all seeds.")?;
+    let mut best_records = best_records.clone();
+    println!("\nBest: {}", sweep::format_combination(best_params));
+
+    // Updates the best known scores and calculates relative scores.
+    if let Some(best_score_file_path) = &config.path.best_score {
+        evaluation::update_best_scores(best_score_file_path, objective, &mut best_records)?;
+    }
+
+    // Outputs the evaluation records of the best values to CSV file.
+    evaluation::write_to_csv(&tune_config.best_record, &best_records)?;
+
+    println!();
+    evaluation::show_statistics(&config.statistics, &best_records)?;
+
+    // Shows the seeds that failed to be evaluated with the best values.
+    if best_records.iter().any(|record| !record.is_accepted()) {
+        println!();
+        evaluation::show_failures(&best_records);
+    }
+
+    Ok(())
+}
+
+/// Evaluates values sampled uniformly at random on all seeds.
+fn random_search(
+    config: &Config,
+    seed_entries: &[SeedEntry],
+    trial_store: &mut TrialStore,
+) -> anyhow::Result<Vec<Trial>> {
+    let trial_num = config.tune.trial_num;
+    let mut trials = vec![];
+
+    for i in 0..trial_num {
+        let mut rng = trial_rng(config, i);
+        let params = to_param_values(&config.params, &sample_uniform(&config.params, &mut rng));
+
+        let records = evaluate_trial(config, seed_entries, trial_store, &params, i, trial_num)?;
+        trials.push((params, records));
+    }
+
+    Ok(trials)
+}
+
+/// Evaluates candidates sampled at random on a few seeds,
+/// and repeatedly keeps the best `1 / reduction_factor` of them while multiplying the seeds by `reduction_factor`.
+///
+/// The seeds of each round are the first ones in the seed list. The last candidate is evaluated on all seeds.
+fn successive_halving(
+    config: &Config,
+    seed_entries: &[SeedEntry],
+    trial_store: &mut TrialStore,
+) -> anyhow::Result<Vec<Trial>> {
+    let tune_config = &config.tune;
+    let reduction_factor = tune_config.reduction_factor;
+    let objective = config.score.objective;
+
+    // Number of rounds after which a single candidate remains.
+    let mut round_num = 0;
+    while reduction_factor.pow(round_num + 1) <= tune_config.trial_num {
+        round_num += 1;
+    }
+
+    let mut seed_num = tune_config
+        .initial_seed_num
+        .unwrap_or_else(|| seed_entries.len().div_ceil(reduction_factor.pow(round_num)))
+        .clamp(1, seed_entries.len());
+
+    let mut candidates = (0..tune_config.trial_num)
+        .map(|i| {
+            let mut rng = trial_rng(config, i);
+            to_param_values(&config.params, &sample_uniform(&config.params, &mut rng))
+        })
+        .collect_vec();
+
+    for round in 1.. {
+        println!(
+            "\n[Round {}] {} candidates on {} seeds",
+            round,
+            candidates.len(),
+            seed_num
+        );
+
+        let mut trials = vec![];
+        for (i, params) in candidates.iter().enumerate() {
+            let records = evaluate_trial(
+                config,
+                &seed_entries[..seed_num],
+                trial_store,
+                params,
+                i,
+                candidates.len(),
+            )?;
+            trials.push((params.clone(), records));
+        }
+
+        trials.sort_by(|(_, records_a), (_, records_b)| {
+            compare_trials(
+                records_a,
+                records_b,
+                objective,
+                trial_store.best_scores.as_ref(),
+            )
+        });
+
+        if trials.len() == 1 || seed_num == seed_entries.len() {
+            // Evaluates the last candidate on all seeds.
+            if seed_num != seed_entries.len() {
+                println!("\n[Final] 1 candidate on {} seeds", seed_entries.len());
+                let params = trials[0].0.clone();
+                let records = evaluate_trial(config, seed_entries, trial_store, &params, 0, 1)?;
+                trials = vec![(params, records)];
+            }
+
+            return Ok(trials);
+        }
+
+        candidates = trials
+            .into_iter()
+            .take(candidates.len().div_ceil(reduction_factor))
+            .map(|(params, _)| params)
+            .collect();
+        seed_num = (seed_num * reduction_factor).min(seed_entries.len());
+    }
+
+    unreachable!()
+}
+
+/// Evaluates values sampled uniformly at random for `startup_trial_num` trials,
+/// and then values suggested by TPE from the previous trials, on all seeds.
+fn tpe(
+    config: &Config,
+    seed_entries: &[SeedEntry],
+    trial_store: &mut TrialStore,
+) -> anyhow::Result<Vec<Trial>> {
+    let trial_num = config.tune.trial_num;
+    let objective = config.score.objective;
+
+    // Sampled points and the evaluation records of the previous trials.
+    let mut history: Vec<(Vec<f64>, Vec<EvaluationRecord>)> = vec![];
+    let mut trials = vec![];
+
+    for i in 0..trial_num {
+        let mut rng = trial_rng(config, i);
+        let point = if i < config.tune.startup_trial_num.max(2) {
+            sample_uniform(&config.params, &mut rng)
+        } else {
+            suggest_by_tpe(
+                &config.params,
+                &history,
+                objective,
+                trial_store.best_scores.as_ref(),
+                &mut rng,
+            )
+        };
+        let params = to_param_values(&config.params, &point);
+
+        let records = evaluate_trial(config, seed_entries, trial_store, &params, i, trial_num)?;
+        history.push((point, records.clone()));
+        trials.push((params, records));
+    }
+
+    Ok(trials)
+}
+
+/// Evaluates the values of the parameters and shows the result.
+fn evaluate_trial(
+    config: &Config,
+    seed_entries: &[SeedEntry],
+    trial_store: &mut TrialStore,
+    params: &[(String, String)],
+    i: usize,
+    trial_num: usize,
+) -> anyhow::Result<Vec<EvaluationRecord>> {
+    println!(
+        "[{}/{}] {}",
+        i + 1,
+        trial_num,
+        sweep::format_combination(params)
+    );
+
+    let records = trial_store.evaluate(config, params, seed_entries)?;
+
+    let scores = accepted_scores(&records);
+    println!(
+        "Avg Score: {} ({} / {} accepted)",
+        if scores.is_empty() {
+            "-".to_owned()
+        } else {
+            format!("{:.3}", statistics::mean(&scores))
+        },
+        scores.len(),
+        records.len()
+    );
+
+    Ok(records)
+}
+
+/// Shows the best trials evaluated on all seeds.
+///
+/// The average relative score is also shown if the best known scores are given.
+fn show_ranking(ranking: &[&Trial], objective: Objective, best_scores: Option<&BestScoreTable>) {
+    let labels = ranking
+        .iter()
+        .map(|(params, _)| sweep::format_combination(params))
+        .collect_vec();
+    let label_width = labels
+        .iter()
+        .map(|label| label.chars().count())
+        .chain(["Parameters".len()])
+        .max()
+        .unwrap();
+
+    println!("[Tune Ranking]");
+    print!(
+        "{:>4}  {:<label_width$}  {:>10}  {:>14}",
+        "Rank", "Parameters", "Accepted", "Avg Score"
+    );
+    if best_scores.is_some() {
+        print!("  {:>14}", "Avg Rel. Score");
+    }
+    println!();
+
+    for (rank, ((_, records), label)) in ranking
+        .iter()
+        .zip(&labels)
+        .take(RANKING_TRIAL_NUM)
+        .enumerate()
+    {
+        let scores = accepted_scores(records);
+
+        print!(
+            "{:>4}  {:<label_width$}  {:>10}  {:>14}",
+            rank + 1,
+            label,
+            format!("{} / {}", scores.len(), records.len()),
+            if scores.is_empty() {
+                "-".to_owned()
+            } else {
+                format!("{:.3}", statistics::mean(&scores))
+            },
+        );
+        if best_scores.is_some() {
+            let relative_scores = comparison_scores(records, objective, best_scores);
+            print!(
+                "  {:>14}",
+                if relative_scores.is_empty() {
+                    "-".to_owned()
+                } else {
+                    format!("{:.3}%", statistics::mean(&relative_scores) * 100.0)
+                }
+            );
+        }
+        println!();
+    }
+}
+
+/// Returns the scores of the accepted seeds.
+fn accepted_scores(records: &[EvaluationRecord]) -> Vec<f64> {
+    records
+        .iter()
+        .filter(|record| record.is_accepted())
+        .filter_map(|record| record.score)
+        .collect()
+}
+
+/// Returns the values compared between trials for the accepted seeds,
+/// which are the relative scores if the best known scores are given, or the scores otherwise.
+fn comparison_scores(
+    records: &[EvaluationRecord],
+    objective: Objective,
+    best_scores: Option<&BestScoreTable>,
+) -> Vec<f64> {
+    let scores = records
+        .iter()
+        .filter(|record| record.is_accepted())
+        .filter_map(|record| Some((record.seed, record.score?)));
+
+    match best_scores {
+        Some(best_scores) => scores
+            .map(|(seed, score)| {
+                let best_score = best_scores.get(seed).unwrap_or(score);
+                relative_score(score, best_score, objective)
+            })
+            .collect(),
+        None => scores.map(|(_, score)| score).collect(),
+    }
+}
+
+/// Updates the best known score for the seed of the record if the record has a score.
+fn update_best_score(
+    best_scores: &mut BestScoreTable,
+    record: &EvaluationRecord,
+    objective: Objective,
+) {
+    if let Some(score) = record.score {
+        best_scores.update(record.seed, score, objective);
+    }
+}
+
+/// Compares two trials evaluated on the same seeds, where the better trial is `Less`.
+///
+/// The trial with more accepted seeds is better, and ties are broken by the average relative score of the accepted seeds
+/// if the best known scores are given, or by their average score otherwise.
+fn compare_trials(
+    records_a: &[EvaluationRecord],
+    records_b: &[EvaluationRecord],
+    objective: Objective,
+    best_scores: Option<&BestScoreTable>,
+) -> Ordering {
+    let scores_a = comparison_scores(records_a, objective, best_scores);
+    let scores_b = comparison_scores(records_b, objective, best_scores);
+
+    // Relative scores are better when larger regardless of the objective.
+    let objective = match best_scores {
+        Some(_) => Objective::Maximize,
+        None => objective,
+    };
+
+    scores_b.len().cmp(&scores_a.len()).then_with(|| {
+        if scores_a.is_empty() {
+            return Ordering::Equal;
+        }
+
+        let avg_a = statistics::mean(&scores_a);
+        let avg_b = statistics::mean(&scores_b);
+        if is_better(avg_a, avg_b, objective) {
+            Ordering::Less
+        } else if is_better(avg_b, avg_a, objective) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    })
+}
+
+/// Returns the fingerprint of the programs with which the trials are evaluated.
+///
+/// It is the SHA-256 hash of the commands, the score retrieval and the commit of the git repository in the current directory.
+/// Uncommitted changes are not reflected.
+fn fingerprint(config: &Config) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{:?}", config.command));
+    hasher.update(format!("{:?}", config.score));
+    hasher.update(history::git_commit().unwrap_or_default());
+
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .join("")
+}
+
+/// Returns the random number generator of the trial, which depends only on `tune.seed` and the index of the trial.
+fn trial_rng(config: &Config, i: usize) -> Rng {
+    Rng::new(config.tune.seed.wrapping_add(i as u64))
+}
+
+/// Samples a point of the search space uniformly at random.
+///
+/// A point is the position in `[0, 1]` for a range, and the index of the value for `values`.
+fn sample_uniform(params: &[ParamConfig], rng: &mut Rng) -> Vec<f64> {
+    params
+        .iter()
+        .map(|param| match &param.values {
+            Some(values) => rng.gen_index(values.len()) as f64,
+            None => rng.next_f64(),
+        })
+        .collect()
+}
+
+/// Converts a point of the search space into the values of the parameters.
+fn to_param_values(params: &[ParamConfig], point: &[f64]) -> Vec<(String, String)> {
+    params
+        .iter()
+        .zip(point)
+        .map(|(param, &x)| {
+            let value = match &param.values {
+                Some(values) => values[x as usize].to_string(),
+                None => param.value_at(x),
+            };
+
+            (param.name.clone(), value)
+        })
+        .collect()
+}
+
+/// Suggests the point that maximizes the ratio of the densities estimated from the good trials and the other trials.
+///
+/// Each parameter is modeled independently. Candidates are sampled from the estimator of the good trials.
+fn suggest_by_tpe(
+    params: &[ParamConfig],
+    history: &[(Vec<f64>, Vec<EvaluationRecord>)],
+    objective: Objective,
+    best_scores: Option<&BestScoreTable>,
+    rng: &mut Rng,
+) -> Vec<f64> {
+    let sorted_history = history
+        .iter()
+        .sorted_by(|(_, records_a), (_, records_b)| {
+            compare_trials(records_a, records_b, objective, best_scores)
+        })
+        .collect_vec();
+
+    let good_num = ((TPE_GAMMA * history.len() as f64).ceil() as usize).clamp(1, history.len() - 1);
+    let (good_trials, bad_trials) = sorted_history.split_at(good_num);
+
+    let estimators = params
+        .iter()
+        .enumerate()
+        .map(|(d, param)| {
+            let good_points = good_trials.iter().map(|(point, _)| point[d]).collect_vec();
+            let bad_points = bad_trials.iter().map(|(point, _)| point[d]).collect_vec();
+
+            match &param.values {
+                Some(values) => (
+                    ParzenEstimator::categorical(&good_points, values.len()),
+                    ParzenEstimator::categorical(&bad_points, values.len()),
+                ),
+                None => (
+                    ParzenEstimator::numeric(&good_points),
+                    ParzenEstimator::numeric(&bad_points),
+                ),
+            }
+        })
+        .collect_vec();
+
+    (0..TPE_CANDIDATE_NUM)
+        .map(|_| {
+            let point = estimators
+                .iter()
+                .map(|(good, _)| good.sample(rng))
+                .collect_vec();
+            let score = estimators
+                .iter()
+                .zip(&point)
+                .map(|((good, bad), &x)| good.density(x).ln() - bad.density(x).ln())
+                .sum::<f64>();
+
+            (point, score)
+        })
+        .max_by(|(_, score_a), (_, score_b)| score_a.total_cmp(score_b))
+        .unwrap()
+        .0
+}
+
+/// Density estimator of a parameter built from the points of trials.
+enum ParzenEstimator {
+    /// Mixture of the uniform distribution on `[0, 1]` and Gaussian kernels at the points.
+    Numeric { mus: Vec<f64>, sigmas: Vec<f64> },
+
+    /// Frequencies of the indices of the values smoothed by adding one to each count.
+    Categorical { weights: Vec<f64> },
+}
+
+impl ParzenEstimator {
+    /// Builds the estimator of a range.
+    ///
+    /// The bandwidth of each kernel is the larger distance to its neighbors, as in the original TPE.
+    fn numeric(points: &[f64]) -> Self {
+        let mus = points
+            .iter()
+            .copied()
+            .sorted_by(f64::total_cmp)
+            .collect_vec();
+        let min_sigma = 1.0 / (mus.len() as f64 + 1.0).min(100.0);
+
+        let sigmas = (0..mus.len())
+            .map(|i| {
+                let left = if i == 0 { mus[i] } else { mus[i] - mus[i - 1] };
+                let right = if i == mus.len() - 1 {
+                    1.0 - mus[i]
+                } else {
+                    mus[i + 1] - mus[i]
+                };
+
+                left.max(right).clamp(min_sigma, 1.0)
+            })
+            .collect();
+
+        Self::Numeric { mus, sigmas }
+    }
+
+    /// Builds the estimator of `values`.
+    fn categorical(points: &[f64], value_num: usize) -> Self {
+        let mut weights = vec![1.0; value_num];
+        for &x in points {
+            weights[x as usize] += 1.0;
+        }
+
+        let total = weights.iter().sum::<f64>();
+        weights.iter_mut().for_each(|weight| *weight /= total);
+
+        Self::Categorical { weights }
+    }
+
+    /// Samples a point from the estimator.
+    fn sample(&self, rng: &mut Rng) -> f64 {
+        match self {
+            Self::Numeric { mus, sigmas } => {
+                // The uniform distribution is chosen as one of the components.
+                let component = rng.gen_index(mus.len() + 1);
+                if component == mus.len() {
+                    return rng.next_f64();
+                }
+
+                // Box-Muller transform.
+                let radius = (-2.0 * (1.0 - rng.next_f64()).ln()).sqrt();
+                let normal = radius * (2.0 * PI * rng.next_f64()).cos();
+
+                (mus[component] + sigmas[component] * normal).clamp(0.0, 1.0)
+            }
+            Self::Categorical { weights } => {
+                let mut r = rng.next_f64();
+                for (i, &weight) in weights.iter().enumerate() {
+                    if r < weight {
+                        return i as f64;
+                    }
+                    r -= weight;
+                }
+
+                (weights.len() - 1) as f64
+            }
+        }
+    }
+
+    /// Returns the density of the estimator at the point.
+    fn density(&self, x: f64) -> f64 {
+        match self {
+            Self::Numeric { mus, sigmas } => {
+                let kernel_sum = mus
+                    .iter()
+                    .zip(sigmas)
+                    .map(|(mu, sigma)| {
+                        let z = (x - mu) / sigma;
+                        (-0.5 * z * z).exp() / (sigma * (2.0 * PI).sqrt())
+                    })
+                    .sum::<f64>();
+
+                (1.0 + kernel_sum) / (mus.len() as f64 + 1.0)
+            }
+            Self::Categorical { weights } => weights[x as usize],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ParamValue;
+
+    fn range_param(name: &str, min: f64, max: f64, step: Option<f64>) -> ParamConfig {
+        ParamConfig {
+            name: name.to_owned(),
+            values: None,
+            min: Some(min),
+            max: Some(max),
+            step,
+            log: false,
+        }
+    }
+
+    fn values_param(name: &str, values: &[&str]) -> ParamConfig {
+        ParamConfig {
+            name: name.to_owned(),
+            values: Some(
+                values
+                    .iter()
+                    .map(|&value| ParamValue::String(value.to_owned()))
+                    .collect(),
+            ),
+            min: None,
+            max: None,
+            step: None,
+            log: false,
+        }
+    }
+
+    fn records(scores: &[Option<f64>]) -> Vec<EvaluationRecord> {
+        scores
+            .iter()
+            .enumerate()
+            .map(|(seed, &score)| {
+                let mut record = EvaluationRecord::from_error(seed, &anyhow::anyhow!("Failed."));
+                if score.is_some() {
+                    record.score = score;
+                    record.status = Status::Accepted;
+                    record.failure = None;
+                }
+
+                record
+            })
+            .collect()
+    }
+
+    #[test]
+    fn numeric_estimator_is_a_density() {
+        let estimator = ParzenEstimator::numeric(&[0.2, 0.3, 0.8]);
+
+        // Integrates the density over `[-5, 6]` by the midpoint rule.
+        // The uniform component contributes `11 / 4` there, and the kernels contribute `3 / 4` in total.
+        let n = 110000;
+        let integral = (0..n)
+            .map(|i| estimator.density(-5.0 + 11.0 * (i as f64 + 0.5) / n as f64))
+            .sum::<f64>()
+            * 11.0
+            / n as f64;
+
+        assert!((integral - 3.5).abs() < 1e-6, "{}", integral);
+        assert!(estimator.density(0.25) > estimator.density(0.55));
+        assert!(estimator.density(1.0) > 0.0);
+
+        let mut rng = Rng::new(0);
+        for _ in 0..1000 {
+            let x = estimator.sample(&mut rng);
+            assert!((0.0..=1.0).contains(&x));
+        }
+    }
+
+    #[test]
+    fn categorical_estimator_smooths_counts() {
+        let estimator = ParzenEstimator::categorical(&[0.0, 0.0, 2.0], 3);
+
+        assert_eq!(estimator.density(0.0), 3.0 / 6.0);
+        assert_eq!(estimator.density(1.0), 1.0 / 6.0);
+        assert_eq!(estimator.density(2.0), 2.0 / 6.0);
+
+        let mut rng = Rng::new(0);
+        let mut counts = [0; 3];
+        for _ in 0..6000 {
+            counts[estimator.sample(&mut rng) as usize] += 1;
+        }
+        assert!(counts[0] > counts[2] && counts[2] > counts[1]);
+    }
+
+    #[test]
+    fn sample_and_convert_points() {
+        let params = [
+            range_param("A", 10.0, 20.0, Some(5.0)),
+            values_param("B", &["x", "y"]),
+        ];
+
+        let point = sample_uniform(&params, &mut Rng::new(1));
+        assert_eq!(point, sample_uniform(&params, &mut Rng::new(1)));
+        assert!((0.0..=1.0).contains(&point[0]));
+        assert!(point[1] == 0.0 || point[1] == 1.0);
+
+        assert_eq!(
+            to_param_values(&params, &[0.6, 1.0]),
+            [
+                ("A".to_owned(), "15".to_owned()),
+                ("B".to_owned(), "y".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn compare_trials_by_accepted_seeds_and_scores() {
+        let a = records(&[Some(10.0), Some(20.0)]);
+        let b = records(&[Some(30.0), None]);
+        let c = records(&[Some(20.0), Some(30.0)]);
+
+        assert_eq!(
+            compare_trials(&a, &b, Objective::Maximize, None),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_trials(&a, &c, Objective::Maximize, None),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_trials(&a, &c, Objective::Minimize, None),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_trials(&a, &a, Objective::Minimize, None),
+            Ordering::Equal
+        );
+
+        // The relative scores weigh each seed equally.
+        let mut best_scores = BestScoreTable::default();
+        best_scores.update(0, 100.0, Objective::Maximize);
+        best_scores.update(1, 10.0, Objective::Maximize);
+        let d = records(&[Some(100.0), Some(1.0)]);
+        let e = records(&[Some(10.0), Some(10.0)]);
+
+        assert_eq!(
+            compare_trials(&d, &e, Objective::Maximize, None),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_trials(&d, &e, Objective::Maximize, Some(&best_scores)),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn suggest_point_near_good_trials() {
+        let params = [
+            range_param("A", 0.0, 1.0, None),
+            values_param("B", &["x", "y"]),
+        ];
+
+        // The score is higher as the first coordinate is closer to 0.9 and the second is 1.
+        let history = (0..20)
+            .map(|i| {
+                let x = i as f64 / 19.0;
+                let y = (i % 2) as f64;
+                let score = 10.0 - (x - 0.9).abs() * 5.0 + y;
+
+                (vec![x, y], records(&[Some(score)]))
+            })
+            .collect_vec();
+
+        let mut rng = Rng::new(0);
+        for _ in 0..10 {
+            let point = suggest_by_tpe(&params, &history, Objective::Maximize, None, &mut rng);
+
+            assert_eq!(point.len(), params.len());
+            assert!((0.0..=1.0).contains(&point[0]));
+            assert!(point[1] == 0.0 || point[1] == 1.0);
+        }
+
+        let points = (0..50)
+            .map(|_| suggest_by_tpe(&params, &history, Objective::Maximize, None, &mut rng))
+            .collect_vec();
+        let mean_x = points.iter().map(|point| point[0]).sum::<f64>() / points.len() as f64;
+        let mean_y = points.iter().map(|point| point[1]).sum::<f64>() / points.len() as f64;
+
+        assert!(mean_x > 0.6, "{}", mean_x);
+        assert!(mean_y > 0.5, "{}", mean_y);
+    }
+}